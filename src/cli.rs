@@ -1,7 +1,11 @@
+use crate::config;
+use crate::format;
+use crate::schema;
 use crate::template;
 use crate::tf;
 use crate::types;
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use std::io::IsTerminal;
 use std::str::FromStr;
 
 #[derive(Parser)]
@@ -12,6 +16,290 @@ pub struct Cli {
     pub command: Option<Commands>,
 }
 
+#[derive(Args, Clone)]
+pub struct PlanArgs {
+    #[clap(
+        short,
+        long,
+        help="File path or glob with terraform.tfplan.json, can be used multiple times.",
+        num_args = 1..,
+    )]
+    file: Vec<String>,
+    #[clap(
+        short,
+        long,
+        help = "Attribute path to ignore in changes, e.g. `tags_all` or `aws_s3_bucket:tags.*`, can be used multiple times."
+    )]
+    ignore: Vec<String>,
+    #[clap(
+        long,
+        help = "JSON config file, e.g. `{\"ignore\": [\"tags_all\"]}`, CLI options are added to it."
+    )]
+    config: Option<String>,
+    #[clap(
+        long,
+        help = "Treat null, empty strings, empty lists and empty maps as equal",
+        default_value = "false"
+    )]
+    normalize_empty: bool,
+    #[clap(
+        long,
+        help = "File with provider schemas from `terraform providers schema -json`"
+    )]
+    provider_schemas: Option<String>,
+    #[clap(
+        long,
+        help = "Actions to exclude, possible options: [create, update, replace, delete, read, no-op], e.g. `no-op,read`.",
+        value_delimiter = ','
+    )]
+    exclude_action: Vec<String>,
+    #[clap(
+        long,
+        help = "Address pattern of changes to include, e.g. `module.app.*`, can be used multiple times."
+    )]
+    include_address: Vec<String>,
+    #[clap(
+        long,
+        help = "Resource type pattern to exclude, e.g. `random_*`, can be used multiple times."
+    )]
+    exclude_type: Vec<String>,
+    #[clap(
+        long,
+        help = "Resource mode to exclude, possible options: [managed, data].",
+        value_delimiter = ','
+    )]
+    exclude_mode: Vec<String>,
+    #[clap(
+        long,
+        help = "Group changes into a tree of the modules they belong to",
+        default_value = "false"
+    )]
+    group_by_module: bool,
+    #[clap(
+        long,
+        help = "Group instances of resources with `count` or `for_each`",
+        default_value = "false"
+    )]
+    group_instances: bool,
+}
+
+impl PlanArgs {
+    fn config(&self) -> Result<config::Config, types::Error> {
+        match &self.config {
+            Some(path) => config::Config::from_file(path).map_err(|e| {
+                types::Error::command("Failed to load config".to_string(), exitcode::CONFIG, e)
+            }),
+            None => Ok(config::Config::default()),
+        }
+    }
+
+    fn options(&self, config: &config::Config) -> Result<tf::Options, types::Error> {
+        let mut ignore_rules = Vec::new();
+        for rule in config.ignore.iter().chain(&self.ignore) {
+            ignore_rules.push(tf::IgnoreRule::from_str(rule).map_err(|e| {
+                types::Error::command(format!("Invalid ignore rule({rule})"), exitcode::USAGE, e)
+            })?);
+        }
+        let schemas = match &self.provider_schemas {
+            Some(path) => schema::Registry::from_file(path).map_err(|e| {
+                types::Error::command(
+                    "Failed to load provider schemas".to_string(),
+                    exitcode::DATAERR,
+                    e,
+                )
+            })?,
+            None => schema::Registry::default(),
+        };
+        Ok(tf::Options {
+            ignore_rules,
+            normalize_empty: self.normalize_empty,
+            schemas,
+            filter: self.filter()?,
+            group_by_module: self.group_by_module,
+            group_instances: self.group_instances,
+        })
+    }
+
+    fn filter(&self) -> Result<tf::Filter, types::Error> {
+        let patterns = |patterns: &[String]| -> Result<Vec<glob::Pattern>, types::Error> {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    glob::Pattern::new(pattern).map_err(|e| {
+                        types::Error::command(
+                            format!("Invalid pattern({pattern})"),
+                            exitcode::USAGE,
+                            e,
+                        )
+                    })
+                })
+                .collect()
+        };
+        for mode in &self.exclude_mode {
+            if mode != "managed" && mode != "data" {
+                return Err(types::Error::command(
+                    format!("Invalid mode({mode})"),
+                    exitcode::USAGE,
+                    types::Error::default(format!("Invalid resource mode: {mode}")),
+                ));
+            }
+        }
+        Ok(tf::Filter {
+            exclude_actions: actions(&self.exclude_action)?,
+            include_addresses: patterns(&self.include_address)?,
+            exclude_types: patterns(&self.exclude_type)?,
+            exclude_modes: self.exclude_mode.clone(),
+        })
+    }
+
+    fn data(&self, config: &config::Config) -> Result<tf::Data, types::Error> {
+        let options = self.options(config)?;
+        tf::Data::from_files(&self.file, &options).map_err(|e| {
+            types::Error::command("Failed to parse plan".to_string(), exitcode::DATAERR, e)
+        })
+    }
+}
+
+fn actions(values: &[String]) -> Result<Vec<tf::Action>, types::Error> {
+    values
+        .iter()
+        .map(|action| {
+            tf::Action::from_str(action).map_err(|e| {
+                types::Error::command(format!("Invalid action({action})"), exitcode::USAGE, e)
+            })
+        })
+        .collect()
+}
+
+fn action_symbols(value: &str) -> Result<template::tera::ActionSymbols, types::Error> {
+    template::tera::ActionSymbols::from_str(value)
+        .map_err(|e| types::Error::command(format!("Invalid symbols({value})"), exitcode::USAGE, e))
+}
+
+#[derive(Args, Clone)]
+#[allow(clippy::struct_field_names)]
+pub struct LimitArgs {
+    #[clap(long, help = "Truncate strings longer than the limit")]
+    max_string_length: Option<usize>,
+    #[clap(
+        long,
+        help = "Truncate added, removed and unchanged values longer than the limit in lines"
+    )]
+    max_lines: Option<usize>,
+    #[clap(long, help = "Collapse values nested deeper than the limit")]
+    max_depth: Option<usize>,
+    #[clap(long, help = "Hide unchanged array elements above the limit")]
+    max_elements: Option<usize>,
+}
+
+impl LimitArgs {
+    fn limits(&self) -> template::tera::ValuesLimits {
+        template::tera::ValuesLimits {
+            max_string_length: self.max_string_length,
+            max_lines: self.max_lines,
+            max_depth: self.max_depth,
+            max_elements: self.max_elements,
+        }
+    }
+}
+
+#[derive(Args, Clone)]
+pub struct SplitArgs {
+    #[clap(
+        long,
+        help = "Maximum output size in characters, defaults to the comment limit of Github (65536) or GitLab (1000000). Larger output is reduced first and split into numbered parts after."
+    )]
+    max_size: Option<usize>,
+    #[clap(
+        long,
+        help = "Directory to write the parts into as part-N.md instead of stdout"
+    )]
+    output_dir: Option<String>,
+}
+
+impl SplitArgs {
+    fn write(&self, parts: &[String], mut stdout: impl std::io::Write) -> Result<(), types::Error> {
+        let Some(output_dir) = &self.output_dir else {
+            writeln!(stdout, "{}", parts.join("\n")).unwrap();
+            return Ok(());
+        };
+
+        let folder = std::path::Path::new(output_dir);
+        std::fs::create_dir_all(folder).map_err(|e| {
+            types::Error::command(
+                format!("Failed to create directory({output_dir})"),
+                exitcode::CANTCREAT,
+                e,
+            )
+        })?;
+        for (index, part) in parts.iter().enumerate() {
+            let file = folder.join(format!("part-{}.md", index + 1));
+            std::fs::write(&file, format!("{part}\n")).map_err(|e| {
+                types::Error::command(
+                    format!("Failed to write file({})", file.to_string_lossy()),
+                    exitcode::CANTCREAT,
+                    e,
+                )
+            })?;
+            writeln!(stdout, "{}", file.to_string_lossy()).unwrap();
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Args, Clone)]
+pub struct SymbolArgs {
+    #[clap(
+        long,
+        help = "Action symbols, possible options: [emoji, shortcodes, ascii, words]. Overrides `symbols` of the config."
+    )]
+    symbols: Option<String>,
+}
+
+impl SymbolArgs {
+    /// Symbols given on the command line, in the config or the default of the command
+    fn symbols(
+        &self,
+        config: &config::Config,
+        default: template::tera::ActionSymbols,
+    ) -> Result<template::tera::ActionSymbols, types::Error> {
+        match self.symbols.as_ref().or(config.symbols.as_ref()) {
+            Some(symbols) => action_symbols(symbols),
+            None => Ok(default),
+        }
+    }
+}
+
+#[derive(Args, Clone)]
+pub struct MarkdownArgs {
+    #[clap(
+        short,
+        long,
+        help = "Wheather to render changed values",
+        default_value = "false"
+    )]
+    changed_values: bool,
+    #[clap(
+        short,
+        long,
+        help = "Values rendering style, possible options: [default, terraform]. Defaults to `default`, `terraform` for `gitlab`."
+    )]
+    style: Option<String>,
+    #[clap(
+        long,
+        help = "Render a table with change counts per plan above the changes",
+        default_value = "false"
+    )]
+    summary: bool,
+    #[command(flatten)]
+    symbols: SymbolArgs,
+    #[command(flatten)]
+    limits: LimitArgs,
+    #[command(flatten)]
+    split: SplitArgs,
+}
+
 #[derive(Subcommand, Clone)]
 pub enum Commands {
     #[command(about = "Render template with advanced options")]
@@ -23,32 +311,125 @@ pub enum Commands {
             default_value = "tera"
         )]
         engine: String,
-        #[clap(
-            short,
-            long,
-            help="File path or glob with terraform.tfplan.json, can be used multiple times.",
-            num_args = 1..,
-        )]
-        file: Vec<String>,
+        #[command(flatten)]
+        plan: PlanArgs,
         #[clap(short, long, help = "Template string")]
         template: String,
+        #[command(flatten)]
+        symbols: SymbolArgs,
     },
     #[command(about = "Render into Github markdown")]
     Github {
+        #[command(flatten)]
+        plan: PlanArgs,
+        #[command(flatten)]
+        markdown: MarkdownArgs,
+    },
+    #[command(about = "Render into Slack Block Kit JSON payload")]
+    Slack {
+        #[command(flatten)]
+        plan: PlanArgs,
+        #[clap(long, help = "Message header", default_value = "Terraform plan")]
+        title: String,
         #[clap(
-            short,
             long,
-            help="File path or glob with terraform.tfplan.json, can be used multiple times.",
-            num_args = 1..
+            help = "Number of changed addresses listed per plan",
+            default_value = "10"
         )]
-        file: Vec<String>,
+        max_changes: usize,
+        #[command(flatten)]
+        symbols: SymbolArgs,
+    },
+    #[command(about = "Render into Microsoft Teams Adaptive Card JSON")]
+    AdaptiveCard {
+        #[command(flatten)]
+        plan: PlanArgs,
+        #[clap(long, help = "Card title", default_value = "Terraform plan")]
+        title: String,
+        #[command(flatten)]
+        symbols: SymbolArgs,
+    },
+    #[command(about = "Render normalized plans into JSON")]
+    Json {
+        #[command(flatten)]
+        plan: PlanArgs,
+        #[clap(long, help = "Pretty-print the output", default_value = "false")]
+        pretty: bool,
+        #[clap(
+            long,
+            help = "Include raw Terraform plan fields",
+            default_value = "false"
+        )]
+        include_raw: bool,
+    },
+    #[command(about = "Render into SARIF log for code scanning")]
+    Sarif {
+        #[command(flatten)]
+        plan: PlanArgs,
+        #[clap(
+            long,
+            help = "Actions reported as results, possible options: [create, update, replace, delete, read, no-op].",
+            value_delimiter = ',',
+            default_value = "delete,replace"
+        )]
+        report_action: Vec<String>,
+    },
+    #[command(about = "Render into JUnit XML report")]
+    Junit {
+        #[command(flatten)]
+        plan: PlanArgs,
+        #[clap(long, help = "Report name", default_value = "Terraform plan")]
+        title: String,
+        #[clap(
+            long,
+            help = "Actions counted as failures, possible options: [create, update, replace, delete, read, no-op], e.g. `delete,replace`.",
+            value_delimiter = ','
+        )]
+        fail_on: Vec<String>,
+    },
+    #[command(about = "Render changes into CSV")]
+    Csv {
+        #[command(flatten)]
+        plan: PlanArgs,
+        #[clap(
+            long,
+            help = "Field delimiter, a single character or `tab`, e.g. `;`.",
+            default_value = ","
+        )]
+        delimiter: String,
+    },
+    #[command(about = "Render into self-contained HTML report")]
+    Html {
+        #[command(flatten)]
+        plan: PlanArgs,
+        #[clap(long, help = "Report title", default_value = "Terraform plan")]
+        title: String,
+        #[command(flatten)]
+        symbols: SymbolArgs,
+    },
+    #[command(about = "Render with colors for the terminal")]
+    Terminal {
+        #[command(flatten)]
+        plan: PlanArgs,
         #[clap(
-            short,
             long,
-            help = "Wheather to render changed values",
+            help = "When to use colors, possible options: [auto, always, never]. `auto` respects `NO_COLOR`.",
+            default_value = "auto"
+        )]
+        color: String,
+        #[clap(
+            long,
+            help = "Write to stdout instead of `$PAGER` or `less` in a terminal",
             default_value = "false"
         )]
-        changed_values: bool,
+        no_pager: bool,
+    },
+    #[command(about = "Render into GitLab merge request markdown")]
+    Gitlab {
+        #[command(flatten)]
+        plan: PlanArgs,
+        #[command(flatten)]
+        markdown: MarkdownArgs,
     },
 }
 
@@ -63,13 +444,48 @@ pub fn root(
     match command {
         Some(Commands::Custom {
             engine,
-            file,
+            plan,
             template,
-        }) => custom(engine, template, file, stdout),
-        Some(Commands::Github {
-            file,
-            changed_values,
-        }) => github(file, *changed_values, stdout),
+            symbols,
+        }) => custom(engine, template, plan, symbols, stdout),
+        Some(Commands::Github { plan, markdown }) => github(plan, markdown, stdout),
+        Some(Commands::Gitlab { plan, markdown }) => gitlab(plan, markdown, stdout),
+        Some(Commands::Slack {
+            plan,
+            title,
+            max_changes,
+            symbols,
+        }) => slack(plan, title, *max_changes, symbols, stdout),
+        Some(Commands::AdaptiveCard {
+            plan,
+            title,
+            symbols,
+        }) => adaptive_card(plan, title, symbols, stdout),
+        Some(Commands::Json {
+            plan,
+            pretty,
+            include_raw,
+        }) => json(plan, *pretty, *include_raw, stdout),
+        Some(Commands::Sarif {
+            plan,
+            report_action,
+        }) => sarif(plan, report_action, stdout),
+        Some(Commands::Junit {
+            plan,
+            title,
+            fail_on,
+        }) => junit(plan, title, fail_on, stdout),
+        Some(Commands::Csv { plan, delimiter }) => csv(plan, delimiter, stdout),
+        Some(Commands::Html {
+            plan,
+            title,
+            symbols,
+        }) => html(plan, title, symbols, stdout),
+        Some(Commands::Terminal {
+            plan,
+            color,
+            no_pager,
+        }) => terminal(plan, color, *no_pager, stdout),
         None => none(stdout, stderr),
     }
 }
@@ -77,18 +493,19 @@ pub fn root(
 fn custom(
     engine: &str,
     template: &str,
-    files: &[String],
+    plan: &PlanArgs,
+    symbols: &SymbolArgs,
     mut stdout: impl std::io::Write,
 ) -> Result<(), types::Error> {
     let engine = template::Engine::from_str(engine).map_err(|e| {
         types::Error::command(format!("Invalid engine({engine})"), exitcode::USAGE, e)
     })?;
+    let config = plan.config()?;
+    let symbols = symbols.symbols(&config, template::tera::ActionSymbols::Emoji)?;
 
-    let data = tf::Data::from_files(files).map_err(|e| {
-        types::Error::command("Failed to parse plan".to_string(), exitcode::DATAERR, e)
-    })?;
+    let data = plan.data(&config)?;
 
-    let result = template::render(&engine, &data, template).map_err(|e| {
+    let result = template::render(&engine, &data, template, &symbols).map_err(|e| {
         types::Error::command(
             "Failed to render template".to_string(),
             exitcode::DATAERR,
@@ -102,16 +519,69 @@ fn custom(
 }
 
 fn github(
-    files: &[String],
-    show_changed_values: bool,
-    mut stdout: impl std::io::Write,
+    plan: &PlanArgs,
+    args: &MarkdownArgs,
+    stdout: impl std::io::Write,
 ) -> Result<(), types::Error> {
-    let data = tf::Data::from_files(files).map_err(|e| {
-        types::Error::command("Failed to parse plan".to_string(), exitcode::DATAERR, e)
-    })?;
+    let max_size = args
+        .split
+        .max_size
+        .unwrap_or(template::split::GITHUB_COMMENT_LIMIT);
+    markdown(
+        plan,
+        args,
+        template::GithubOptions::default(),
+        max_size,
+        stdout,
+    )
+}
+
+fn gitlab(
+    plan: &PlanArgs,
+    args: &MarkdownArgs,
+    stdout: impl std::io::Write,
+) -> Result<(), types::Error> {
+    let max_size = args
+        .split
+        .max_size
+        .unwrap_or(template::split::GITLAB_NOTE_LIMIT);
+    markdown(
+        plan,
+        args,
+        template::GithubOptions::gitlab(),
+        max_size,
+        stdout,
+    )
+}
+
+/// Renders Github or GitLab markdown, the defaults set the style and symbols of the command
+fn markdown(
+    plan: &PlanArgs,
+    args: &MarkdownArgs,
+    defaults: template::GithubOptions,
+    max_size: usize,
+    stdout: impl std::io::Write,
+) -> Result<(), types::Error> {
+    let config = plan.config()?;
+    let style = match &args.style {
+        Some(style) => template::tera::ValuesStyle::from_str(style).map_err(|e| {
+            types::Error::command(format!("Invalid style({style})"), exitcode::USAGE, e)
+        })?,
+        None => defaults.style.clone(),
+    };
+    let options = template::GithubOptions {
+        show_changed_values: args.changed_values,
+        style,
+        normalize_empty: plan.normalize_empty,
+        limits: args.limits.limits(),
+        summary: args.summary,
+        symbols: args.symbols.symbols(&config, defaults.symbols)?,
+    };
 
-    // Should never fail as the template is hardcoded
-    let result = template::render_github(&data, show_changed_values).map_err(|e| {
+    let data = plan.data(&config)?;
+
+    // The template is hardcoded, only a summary larger than the maximum size fails
+    let parts = template::render_github_parts(&data, &options, max_size).map_err(|e| {
         types::Error::command(
             "Failed to render template".to_string(),
             exitcode::DATAERR,
@@ -119,11 +589,234 @@ fn github(
         )
     })?;
 
+    args.split.write(&parts, stdout)
+}
+
+fn slack(
+    plan: &PlanArgs,
+    title: &str,
+    max_changes: usize,
+    symbols: &SymbolArgs,
+    mut stdout: impl std::io::Write,
+) -> Result<(), types::Error> {
+    let config = plan.config()?;
+    let symbols = symbols.symbols(&config, template::tera::ActionSymbols::Shortcodes)?;
+
+    let data = plan.data(&config)?;
+
+    let options = format::slack::SlackOptions {
+        title: title.to_string(),
+        max_changes,
+        symbols,
+    };
+
+    // Should never fail as the payload only contains strings and numbers
+    let result = format::slack::render_json(&data, &options).map_err(|e| {
+        types::Error::command(
+            "Failed to render payload".to_string(),
+            exitcode::SOFTWARE,
+            e,
+        )
+    })?;
+
+    writeln!(stdout, "{result}").unwrap();
+
+    Ok(())
+}
+
+fn adaptive_card(
+    plan: &PlanArgs,
+    title: &str,
+    symbols: &SymbolArgs,
+    mut stdout: impl std::io::Write,
+) -> Result<(), types::Error> {
+    let config = plan.config()?;
+    let symbols = symbols.symbols(&config, template::tera::ActionSymbols::Emoji)?;
+
+    let data = plan.data(&config)?;
+
+    let options = format::adaptive_card::AdaptiveCardOptions {
+        title: title.to_string(),
+        symbols,
+    };
+
+    // Should never fail as the card only contains strings and booleans
+    let result = format::adaptive_card::render_json(&data, &options).map_err(|e| {
+        types::Error::command("Failed to render card".to_string(), exitcode::SOFTWARE, e)
+    })?;
+
+    writeln!(stdout, "{result}").unwrap();
+
+    Ok(())
+}
+
+fn json(
+    plan: &PlanArgs,
+    pretty: bool,
+    include_raw: bool,
+    mut stdout: impl std::io::Write,
+) -> Result<(), types::Error> {
+    let data = plan.data(&plan.config()?)?;
+
+    let options = format::json::JsonOptions {
+        pretty,
+        include_raw,
+    };
+
+    let result = format::json::render_json(&data, &options).map_err(|e| {
+        types::Error::command("Failed to render output".to_string(), exitcode::SOFTWARE, e)
+    })?;
+
+    writeln!(stdout, "{result}").unwrap();
+
+    Ok(())
+}
+
+fn sarif(
+    plan: &PlanArgs,
+    report_action: &[String],
+    mut stdout: impl std::io::Write,
+) -> Result<(), types::Error> {
+    let actions = actions(report_action)?;
+
+    let data = plan.data(&plan.config()?)?;
+
+    let options = format::sarif::SarifOptions {
+        actions,
+        root: std::env::current_dir().ok(),
+    };
+
+    let result = format::sarif::render_json(&data, &options).map_err(|e| {
+        types::Error::command("Failed to render log".to_string(), exitcode::SOFTWARE, e)
+    })?;
+
     writeln!(stdout, "{result}").unwrap();
 
     Ok(())
 }
 
+fn junit(
+    plan: &PlanArgs,
+    title: &str,
+    fail_on: &[String],
+    mut stdout: impl std::io::Write,
+) -> Result<(), types::Error> {
+    let failure_actions = actions(fail_on)?;
+
+    let data = plan.data(&plan.config()?)?;
+
+    let options = format::junit::JunitOptions {
+        title: title.to_string(),
+        failure_actions,
+        normalize_empty: plan.normalize_empty,
+    };
+
+    writeln!(stdout, "{}", format::junit::render(&data, &options)).unwrap();
+
+    Ok(())
+}
+
+fn csv(
+    plan: &PlanArgs,
+    delimiter: &str,
+    mut stdout: impl std::io::Write,
+) -> Result<(), types::Error> {
+    let delimiter = format::csv::parse_delimiter(delimiter).map_err(|e| {
+        types::Error::command(
+            format!("Invalid delimiter({delimiter})"),
+            exitcode::USAGE,
+            e,
+        )
+    })?;
+
+    let data = plan.data(&plan.config()?)?;
+
+    let options = format::csv::CsvOptions { delimiter };
+
+    writeln!(stdout, "{}", format::csv::render(&data, &options)).unwrap();
+
+    Ok(())
+}
+
+fn html(
+    plan: &PlanArgs,
+    title: &str,
+    symbols: &SymbolArgs,
+    mut stdout: impl std::io::Write,
+) -> Result<(), types::Error> {
+    let config = plan.config()?;
+    let symbols = symbols.symbols(&config, template::tera::ActionSymbols::Emoji)?;
+
+    let data = plan.data(&config)?;
+
+    let options = format::html::HtmlOptions {
+        title: title.to_string(),
+        normalize_empty: plan.normalize_empty,
+        symbols,
+    };
+
+    writeln!(stdout, "{}", format::html::render(&data, &options)).unwrap();
+
+    Ok(())
+}
+
+fn terminal(
+    plan: &PlanArgs,
+    color: &str,
+    no_pager: bool,
+    mut stdout: impl std::io::Write,
+) -> Result<(), types::Error> {
+    let color = format::terminal::ColorChoice::from_str(color).map_err(|e| {
+        types::Error::command(format!("Invalid color({color})"), exitcode::USAGE, e)
+    })?;
+
+    let data = plan.data(&plan.config()?)?;
+
+    let is_terminal = std::io::stdout().is_terminal();
+    let options = format::terminal::TerminalOptions {
+        color: color.enabled(is_terminal, std::env::var("NO_COLOR").ok().as_deref()),
+        normalize_empty: plan.normalize_empty,
+    };
+
+    let result = format::terminal::render(&data, &options);
+
+    if is_terminal && !no_pager && page(&result) {
+        return Ok(());
+    }
+    writeln!(stdout, "{result}").unwrap();
+
+    Ok(())
+}
+
+/// Pipes the text into `$PAGER` or `less`, returns false when the pager cannot be started.
+/// `less` exits right away when the text fits on the screen.
+fn page(text: &str) -> bool {
+    use std::io::Write;
+
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| "less".to_string());
+    let mut args = pager.split_whitespace();
+    let Some(program) = args.next() else {
+        return false;
+    };
+
+    let mut command = std::process::Command::new(program);
+    command.args(args).stdin(std::process::Stdio::piped());
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    let Ok(mut child) = command.spawn() else {
+        return false;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // Fails when the pager is closed before reading everything
+        let _ = writeln!(stdin, "{text}");
+    }
+    child.wait().is_ok()
+}
+
 fn none(
     mut _stdout: impl std::io::Write,
     mut _stderr: impl std::io::Write,
@@ -0,0 +1,81 @@
+use crate::types;
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// Options read from a JSON config file, CLI options are added to them
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// Action symbols of the commands rendering actions, e.g. `ascii`
+    pub symbols: Option<String>,
+}
+
+impl FromStr for Config {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+impl Config {
+    /// # Errors
+    /// Returns an error if the file can't be read or parsed
+    pub fn from_file(path: &str) -> Result<Self, types::Error> {
+        let raw_file = std::fs::read_to_string(path)
+            .map_err(|e| types::Error::chain(format!("Failed to read file({path})"), e))?;
+        Config::from_str(&raw_file)
+            .map_err(|e| types::Error::chain(format!("Failed to parse file({path})"), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils;
+
+    mod from_file {
+        use super::*;
+
+        #[test]
+        fn default() {
+            let path = utils::test::get_test_data_file_path("config/ignore.json");
+            let config = Config::from_file(&path).unwrap();
+
+            assert_eq!(
+                config.ignore,
+                vec!["terraform_data:input", "output", "triggers.*"]
+            );
+        }
+
+        #[test]
+        fn symbols() {
+            let path = utils::test::get_test_data_file_path("config/symbols.json");
+            let config = Config::from_file(&path).unwrap();
+
+            assert_eq!(config.symbols, Some("ascii".to_string()));
+            assert!(config.ignore.is_empty());
+        }
+
+        #[test]
+        fn empty() {
+            assert_eq!(Config::from_str("{}").unwrap(), Config::default());
+        }
+
+        #[test]
+        fn invalid_path() {
+            let config = Config::from_file("invalid path");
+            assert_eq!(
+                config.unwrap_err().full_message(),
+                "Failed to read file(invalid path). No such file or directory (os error 2)"
+            );
+        }
+
+        #[test]
+        fn unknown_field() {
+            assert!(Config::from_str(r#"{"ignored": []}"#).is_err());
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use crate::format;
+use crate::template::tera::{render_action, ActionSymbols};
+use crate::tf;
+use crate::types;
+use itertools::Itertools;
+use serde::Serialize;
+
+pub const SCHEMA: &str = "http://adaptivecards.io/schemas/adaptive-card.json";
+/// Latest version supported by Teams
+pub const VERSION: &str = "1.5";
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Fact {
+    pub title: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum Action {
+    #[serde(rename = "Action.ToggleVisibility", rename_all = "camelCase")]
+    ToggleVisibility {
+        title: String,
+        target_elements: Vec<String>,
+    },
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all_fields = "camelCase")]
+pub enum Element {
+    TextBlock {
+        text: String,
+        wrap: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        size: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        weight: Option<String>,
+    },
+    FactSet {
+        facts: Vec<Fact>,
+    },
+    ActionSet {
+        actions: Vec<Action>,
+    },
+    Container {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        separator: bool,
+        is_visible: bool,
+        items: Vec<Element>,
+    },
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Card {
+    #[serde(rename = "type")]
+    pub type_: String,
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    pub version: String,
+    pub body: Vec<Element>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdaptiveCardOptions {
+    pub title: String,
+    pub symbols: ActionSymbols,
+}
+
+impl Default for AdaptiveCardOptions {
+    fn default() -> Self {
+        AdaptiveCardOptions {
+            title: "Terraform plan".to_string(),
+            symbols: ActionSymbols::Emoji,
+        }
+    }
+}
+
+fn text_block(text: String) -> Element {
+    Element::TextBlock {
+        text,
+        wrap: true,
+        size: None,
+        weight: None,
+    }
+}
+
+/// Characters with a meaning in markdown, e.g. `-` starts a list item and `_` emphasis
+const MARKDOWN_CHARS: &str = "\\`*_{}[]()#+-.!<>~|";
+
+/// Escapes markdown in the content of text blocks and facts, which have no code spans
+#[must_use]
+pub fn escape(text: &str) -> String {
+    text.chars()
+        .map(|char| {
+            if MARKDOWN_CHARS.contains(char) {
+                format!("\\{char}")
+            } else {
+                char.to_string()
+            }
+        })
+        .collect()
+}
+
+fn render_text_action(action: &tf::Action, symbols: &ActionSymbols) -> String {
+    escape(&render_action(action, symbols))
+}
+
+fn render_actions(actions: &[tf::Action], symbols: &ActionSymbols) -> String {
+    actions
+        .iter()
+        .map(|action| render_text_action(action, symbols))
+        .collect()
+}
+
+fn plan_container(
+    index: usize,
+    plan_key: &str,
+    plan: &tf::Plan,
+    symbols: &ActionSymbols,
+) -> Element {
+    let mut items = vec![Element::TextBlock {
+        text: format!(
+            "{}{}",
+            render_actions(plan.unique_actions(), symbols),
+            escape(plan_key)
+        ),
+        wrap: true,
+        size: None,
+        weight: Some("Bolder".to_string()),
+    }];
+
+    let facts: Vec<Fact> = format::counts_by_action(plan.counts())
+        .iter()
+        .filter(|(_, count, _)| *count > 0)
+        .map(|(action, count, name)| Fact {
+            title: format!("{} {name}", render_text_action(action, symbols).trim_end()),
+            value: count.to_string(),
+        })
+        .collect();
+    if facts.is_empty() {
+        items.push(text_block("No resource changes".to_string()));
+    } else {
+        items.push(Element::FactSet { facts });
+    }
+
+    let changes: Vec<Element> = format::changed(plan)
+        .map(|change| {
+            text_block(format!(
+                "{}{}",
+                render_text_action(&change.action, symbols),
+                escape(&change.address)
+            ))
+        })
+        .collect();
+    if !changes.is_empty() {
+        let id = format!("plan-{index}");
+        items.push(Element::ActionSet {
+            actions: vec![Action::ToggleVisibility {
+                title: format!("Show changes ({})", changes.len()),
+                target_elements: vec![id.clone()],
+            }],
+        });
+        items.push(Element::Container {
+            id: Some(id),
+            separator: false,
+            is_visible: false,
+            items: changes,
+        });
+    }
+
+    Element::Container {
+        id: None,
+        separator: true,
+        is_visible: true,
+        items,
+    }
+}
+
+#[must_use]
+pub fn render(data: &tf::Data, options: &AdaptiveCardOptions) -> Card {
+    let mut body = vec![
+        Element::TextBlock {
+            text: options.title.clone(),
+            wrap: true,
+            size: Some("Large".to_string()),
+            weight: Some("Bolder".to_string()),
+        },
+        text_block(format!(
+            "Plan: {} to add, {} to change, {} to destroy.",
+            data.counts.add, data.counts.change, data.counts.destroy
+        )),
+    ];
+
+    for (index, plan_key) in data.plans.keys().sorted().enumerate() {
+        body.push(plan_container(
+            index,
+            plan_key,
+            &data.plans[plan_key],
+            &options.symbols,
+        ));
+    }
+
+    Card {
+        type_: "AdaptiveCard".to_string(),
+        schema: SCHEMA.to_string(),
+        version: VERSION.to_string(),
+        body,
+    }
+}
+
+/// # Errors
+/// Returns an error if the card cannot be serialized
+pub fn render_json(data: &tf::Data, options: &AdaptiveCardOptions) -> Result<String, types::Error> {
+    serde_json::to_string(&render(data, options))
+        .map_err(|e| types::Error::chain("Failed to serialize card".to_string(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize() {
+        let card = Card {
+            type_: "AdaptiveCard".to_string(),
+            schema: SCHEMA.to_string(),
+            version: VERSION.to_string(),
+            body: vec![Element::Container {
+                id: Some("plan-0".to_string()),
+                separator: false,
+                is_visible: false,
+                items: vec![Element::ActionSet {
+                    actions: vec![Action::ToggleVisibility {
+                        title: "Show".to_string(),
+                        target_elements: vec!["plan-0".to_string()],
+                    }],
+                }],
+            }],
+        };
+
+        assert_eq!(
+            serde_json::to_value(&card).unwrap(),
+            serde_json::json!({
+                "type": "AdaptiveCard",
+                "$schema": SCHEMA,
+                "version": VERSION,
+                "body": [{
+                    "type": "Container",
+                    "id": "plan-0",
+                    "isVisible": false,
+                    "items": [{
+                        "type": "ActionSet",
+                        "actions": [{
+                            "type": "Action.ToggleVisibility",
+                            "title": "Show",
+                            "targetElements": ["plan-0"]
+                        }]
+                    }]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn plan() {
+        let plan = tf::tests::get_test_plan(&tf::tests::PlanType::DeleteCreate);
+        let container = plan_container(1, "plan", &plan, &ActionSymbols::Emoji);
+
+        assert_eq!(
+            serde_json::to_value(&container).unwrap(),
+            serde_json::json!({
+                "type": "Container",
+                "separator": true,
+                "isVisible": true,
+                "items": [
+                    {"type": "TextBlock", "text": "♻️plan", "wrap": true, "weight": "Bolder"},
+                    {"type": "FactSet", "facts": [{"title": "♻️ replace", "value": "1"}]},
+                    {"type": "ActionSet", "actions": [{
+                        "type": "Action.ToggleVisibility",
+                        "title": "Show changes (1)",
+                        "targetElements": ["plan-1"]
+                    }]},
+                    {"type": "Container", "id": "plan-1", "isVisible": false, "items": [
+                        {"type": "TextBlock", "text": "♻️null\\_resource\\.foo\\-bar", "wrap": true}
+                    ]}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn ascii_symbols() {
+        let plan = tf::tests::get_test_plan(&tf::tests::PlanType::DeleteCreate);
+        let container = plan_container(1, r#"plans/x["a_b*c"]"#, &plan, &ActionSymbols::Ascii);
+        let value = serde_json::to_value(&container).unwrap();
+
+        assert_eq!(value["items"][0]["text"], r#"\-/\+ plans/x\["a\_b\*c"\]"#);
+        assert_eq!(value["items"][1]["facts"][0]["title"], r"\-/\+ replace");
+        assert_eq!(
+            value["items"][3]["items"][0]["text"],
+            r"\-/\+ null\_resource\.foo\-bar"
+        );
+    }
+
+    #[test]
+    fn no_changes() {
+        let plan = tf::tests::get_test_plan(&tf::tests::PlanType::NoResources);
+        let Element::Container { items, .. } =
+            plan_container(0, "plan", &plan, &ActionSymbols::Emoji)
+        else {
+            panic!("container expected");
+        };
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1], text_block("No resource changes".to_string()));
+    }
+}
@@ -0,0 +1,161 @@
+use crate::tf;
+use crate::types;
+use itertools::Itertools;
+
+pub const HEADER: [&str; 7] = [
+    "plan",
+    "address",
+    "module",
+    "type",
+    "action",
+    "changed_attributes",
+    "sensitive_changed",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsvOptions {
+    pub delimiter: char,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions { delimiter: ',' }
+    }
+}
+
+/// Parses a single character delimiter, `tab` and `\t` stand for the tab character
+///
+/// # Errors
+/// Returns an error if the delimiter is not a single character or is a quote or line break
+pub fn parse_delimiter(s: &str) -> Result<char, types::Error> {
+    if s == "tab" || s == "\\t" {
+        return Ok('\t');
+    }
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(delimiter), None) if !matches!(delimiter, '"' | '\n' | '\r') => Ok(delimiter),
+        _ => Err(types::Error::default(format!("Invalid delimiter: {s}"))),
+    }
+}
+
+/// Quotes the field when it contains the delimiter, quotes or line breaks, quotes are doubled
+#[must_use]
+pub fn quote(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        return format!("\"{}\"", field.replace('"', "\"\""));
+    }
+    field.to_string()
+}
+
+/// Checks for sensitive values whose unmasked values differ, the diff is built before masking
+fn sensitive_changed(nodes: &[tf::DiffNode]) -> bool {
+    nodes
+        .iter()
+        .filter(|node| !node.ignored && !node.cosmetic)
+        .any(|node| {
+            (node.sensitive && node.kind != tf::DiffKind::Unchanged)
+                || sensitive_changed(&node.children)
+        })
+}
+
+fn row(plan_key: &str, change: &tf::Change) -> [String; 7] {
+    let changed_attributes = change
+        .diff
+        .iter()
+        .filter(|node| node.is_significant())
+        .count();
+    [
+        plan_key.to_string(),
+        change.address.clone(),
+        tf::change_module_path(change).join("."),
+        change.type_.clone(),
+        change.action.as_str().to_string(),
+        changed_attributes.to_string(),
+        sensitive_changed(&change.diff).to_string(),
+    ]
+}
+
+/// Renders a header and one row per change, plans are sorted by key
+#[must_use]
+pub fn render(data: &tf::Data, options: &CsvOptions) -> String {
+    let line = |fields: &[&str]| {
+        fields
+            .iter()
+            .map(|field| quote(field, options.delimiter))
+            .join(&options.delimiter.to_string())
+    };
+
+    let mut lines = vec![line(&HEADER)];
+    for plan_key in data.plans.keys().sorted() {
+        for change in data.plans[plan_key].changes() {
+            let fields = row(plan_key, change);
+            lines.push(line(&fields.each_ref().map(String::as_str)));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delimiter() {
+        assert_eq!(parse_delimiter(";").unwrap(), ';');
+        assert_eq!(parse_delimiter("tab").unwrap(), '\t');
+        assert_eq!(parse_delimiter("\\t").unwrap(), '\t');
+        assert!(parse_delimiter(",,").is_err());
+        assert!(parse_delimiter("\"").is_err());
+        assert!(parse_delimiter("").is_err());
+    }
+
+    #[test]
+    fn quoting() {
+        assert_eq!(quote("aws_s3_bucket.this", ','), "aws_s3_bucket.this");
+        assert_eq!(
+            quote(r#"module.app["a,b"].aws_s3_bucket.this"#, ','),
+            r#""module.app[""a,b""].aws_s3_bucket.this""#
+        );
+        assert_eq!(quote("a,b", '\t'), "a,b");
+        assert_eq!(quote("a\tb", '\t'), "\"a\tb\"");
+    }
+
+    #[test]
+    fn rows() {
+        let plan = tf::tests::get_test_plan(&tf::tests::PlanType::Sensitive);
+        let sensitive = row("plan", &plan.changes()[0]);
+
+        assert_eq!(sensitive[1], "random_bytes.test");
+        assert_eq!(sensitive[2], "");
+        assert_eq!(sensitive[4], "replace");
+        assert_eq!(sensitive[6], "true");
+
+        let plan = tf::tests::get_test_plan(&tf::tests::PlanType::Update);
+        let update = row("plan", &plan.changes()[0]);
+
+        assert_eq!(update[4], "update");
+        assert_eq!(update[6], "false");
+    }
+
+    #[test]
+    fn module_address() {
+        let json = tf::tests::get_test_plan_json(&tf::tests::PlanType::Update);
+        let mut raw: tf::RawPlan = json.parse().unwrap();
+        raw.resource_changes.as_mut().unwrap()[0].module_address = Some("module.app".to_string());
+        let plan = tf::Plan::from_raw(raw, &tf::Options::default());
+
+        assert_eq!(row("plan", &plan.changes()[0])[2], "module.app");
+    }
+
+    #[test]
+    fn sensitive_update() {
+        let path =
+            crate::utils::test::get_test_data_file_path("plans/artificial/sensitive-update.json");
+        let raw = tf::RawPlan::from_file(&path).unwrap();
+        let plan = tf::Plan::from_raw(raw, &tf::Options::default());
+        let update = row("plan", &plan.changes()[0]);
+
+        assert_eq!(update[4], "update");
+        assert_eq!(update[5..], ["1", "true"]);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::format;
+use crate::schema;
+use crate::template::tera::{render_action, render_terraform_change, ActionSymbols, ValuesLimits};
+use crate::tf;
+use itertools::Itertools;
+
+const STYLE: &str = "body { font-family: -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; margin: 2em; color: #1f2328; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+th, td { border: 1px solid #d1d9e0; padding: 0.3em 0.8em; }
+td.count { text-align: right; }
+tr.total { font-weight: bold; }
+.filter { margin-bottom: 1em; }
+.filter label { margin-right: 1em; }
+details.change { margin: 0.3em 0; }
+summary { cursor: pointer; }
+pre.diff { background: #f6f8fa; padding: 0.8em; overflow-x: auto; }
+pre.diff .add { color: #1a7f37; background: #dafbe1; }
+pre.diff .remove { color: #cf222e; background: #ffebe9; }
+pre.diff .change, pre.diff .replace { color: #9a6700; background: #fff8c5; }
+pre.diff .read { color: #0969da; }";
+
+/// Hides the changes of unchecked actions
+const SCRIPT: &str = "document.querySelectorAll('.filter input').forEach(function (input) {
+  input.addEventListener('change', function () {
+    document.querySelectorAll('.change[data-action=\"' + input.value + '\"]').forEach(function (change) {
+      change.hidden = !input.checked;
+    });
+  });
+});";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HtmlOptions {
+    pub title: String,
+    pub normalize_empty: bool,
+    pub symbols: ActionSymbols,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        HtmlOptions {
+            title: "Terraform plan".to_string(),
+            normalize_empty: false,
+            symbols: ActionSymbols::Emoji,
+        }
+    }
+}
+
+/// Escapes text for HTML elements and attributes
+#[must_use]
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn render_diff(change: &tf::Change, schemas: &schema::Registry, options: &HtmlOptions) -> String {
+    render_terraform_change(
+        change,
+        schemas,
+        options.normalize_empty,
+        &ValuesLimits::default(),
+    )
+    .lines()
+    .map(|line| match format::diff_line_class(line) {
+        Some(class) => format!("<span class=\"{class}\">{}</span>", escape(line)),
+        None => escape(line),
+    })
+    .join("\n")
+}
+
+fn count_cells(counts: &tf::ActionCounts) -> String {
+    format::counts_by_action(counts)
+        .iter()
+        .map(|(_, count, _)| format!("<td class=\"count\">{count}</td>"))
+        .join("")
+}
+
+fn summary_table(data: &tf::Data, options: &HtmlOptions) -> Vec<String> {
+    let header = format::counts_by_action(&data.counts)
+        .iter()
+        .map(|(action, _, noun)| {
+            format!(
+                "<th>{}{noun}</th>",
+                escape(&render_action(action, &options.symbols))
+            )
+        })
+        .join("");
+    let mut lines = vec![
+        "<table class=\"summary\">".to_string(),
+        format!("<tr><th>Plan</th>{header}</tr>"),
+    ];
+    for plan_key in data.plans.keys().sorted() {
+        lines.push(format!(
+            "<tr><td>{}</td>{}</tr>",
+            escape(plan_key),
+            count_cells(data.plans[plan_key].counts())
+        ));
+    }
+    lines.push(format!(
+        "<tr class=\"total\"><td>Total</td>{}</tr>",
+        count_cells(&data.counts)
+    ));
+    lines.push("</table>".to_string());
+    lines
+}
+
+/// Checkboxes for the actions present in the plans
+fn action_filter(data: &tf::Data, options: &HtmlOptions) -> Vec<String> {
+    let mut lines = vec!["<div class=\"filter\">".to_string()];
+    for (action, count, noun) in format::counts_by_action(&data.counts) {
+        if count > 0 {
+            lines.push(format!(
+                "<label><input type=\"checkbox\" value=\"{}\" checked> {}{noun} ({count})</label>",
+                action.as_str(),
+                escape(&render_action(&action, &options.symbols))
+            ));
+        }
+    }
+    lines.push("</div>".to_string());
+    lines
+}
+
+fn plan_section(
+    plan_key: &str,
+    plan: &tf::Plan,
+    schemas: &schema::Registry,
+    options: &HtmlOptions,
+) -> Vec<String> {
+    let actions: String = plan
+        .unique_actions()
+        .iter()
+        .map(|action| escape(&render_action(action, &options.symbols)))
+        .collect();
+    let mut lines = vec![
+        "<section class=\"plan\">".to_string(),
+        format!("<h2>{actions}{}</h2>", escape(plan_key)),
+    ];
+    if plan.changes().is_empty() {
+        lines.push("<p>No resource changes</p>".to_string());
+    }
+    for change in plan.changes() {
+        lines.push(format!(
+            "<details class=\"change\" data-action=\"{}\">",
+            change.action.as_str()
+        ));
+        lines.push(format!(
+            "<summary>{}{}</summary>",
+            escape(&render_action(&change.action, &options.symbols)),
+            escape(&change.address)
+        ));
+        lines.push(format!(
+            "<pre class=\"diff\">{}</pre>",
+            render_diff(change, schemas, options)
+        ));
+        lines.push("</details>".to_string());
+    }
+    lines.push("</section>".to_string());
+    lines
+}
+
+/// Renders a self-contained page, styles and scripts are inlined
+#[must_use]
+pub fn render(data: &tf::Data, options: &HtmlOptions) -> String {
+    let title = escape(&options.title);
+    let mut lines = vec![
+        "<!DOCTYPE html>".to_string(),
+        "<html lang=\"en\">".to_string(),
+        "<head>".to_string(),
+        "<meta charset=\"utf-8\">".to_string(),
+        format!("<title>{title}</title>"),
+        format!("<style>\n{STYLE}\n</style>"),
+        "</head>".to_string(),
+        "<body>".to_string(),
+        format!("<h1>{title}</h1>"),
+        format!(
+            "<p>Plan: {} to add, {} to change, {} to destroy.</p>",
+            data.counts.add, data.counts.change, data.counts.destroy
+        ),
+    ];
+    lines.extend(summary_table(data, options));
+    lines.extend(action_filter(data, options));
+    for plan_key in data.plans.keys().sorted() {
+        lines.extend(plan_section(
+            plan_key,
+            &data.plans[plan_key],
+            &data.schemas,
+            options,
+        ));
+    }
+    lines.push(format!("<script>\n{SCRIPT}\n</script>"));
+    lines.push("</body>".to_string());
+    lines.push("</html>".to_string());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_html() {
+        assert_eq!(
+            escape(r#"<script>alert("a" & 'b')</script>"#),
+            "&lt;script&gt;alert(&quot;a&quot; &amp; &#39;b&#39;)&lt;/script&gt;"
+        );
+    }
+
+    #[test]
+    fn diff() {
+        let plan = tf::tests::get_test_plan(&tf::tests::PlanType::Create);
+        let diff = render_diff(
+            &plan.changes()[0],
+            &schema::Registry::default(),
+            &HtmlOptions::default(),
+        );
+
+        assert_eq!(
+            diff,
+            "  # terraform_data.foo-bar will be created
+<span class=\"add\">  + resource &quot;terraform_data&quot; &quot;foo-bar&quot; {</span>
+<span class=\"add\">+     id     = (known after apply)</span>
+<span class=\"add\">+     input  = &quot;foo&quot;</span>
+<span class=\"add\">+     output = (known after apply)</span>
+    }"
+        );
+    }
+
+    #[test]
+    fn escaped_values() {
+        let data = tf::tests::get_test_data();
+        let options = HtmlOptions {
+            title: "<b>plan</b>".to_string(),
+            ..HtmlOptions::default()
+        };
+        let result = render(&data, &options);
+
+        assert!(result.contains("<title>&lt;b&gt;plan&lt;/b&gt;</title>"));
+        assert!(!result.contains("<b>"));
+    }
+
+    #[test]
+    fn ascii_symbols() {
+        let data = tf::tests::get_test_data();
+        let options = HtmlOptions {
+            symbols: ActionSymbols::Ascii,
+            ..HtmlOptions::default()
+        };
+        let result = render(&data, &options);
+
+        assert!(result.contains("<th>&lt;= read</th>"));
+        assert!(!result.contains('✅'));
+    }
+}
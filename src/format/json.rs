@@ -0,0 +1,335 @@
+use crate::tf;
+use crate::types;
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Version of the output schema, incremented on breaking changes
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Typed value, e.g. `{"type": "string", "value": "foo"}`,
+/// `null` and `sensitive` values have no `value` field
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum Value {
+    String(String),
+    Number(serde_json::Number),
+    Boolean(bool),
+    Array(Vec<Value>),
+    Object(BTreeMap<String, Value>),
+    Null,
+    Sensitive,
+}
+
+impl From<&tf::Value> for Value {
+    fn from(value: &tf::Value) -> Self {
+        match value {
+            tf::Value::String(value) => Value::String(value.clone()),
+            tf::Value::Integer(value) => Value::Number(serde_json::Number::from(*value)),
+            // JSON has no infinity and NaN, they are kept as strings
+            tf::Value::Float(value) => serde_json::Number::from_f64(*value)
+                .map_or_else(|| Value::String(value.to_string()), Value::Number),
+            tf::Value::Number(value) => serde_json::Number::from_str(value)
+                .map_or_else(|_| Value::String(value.clone()), Value::Number),
+            tf::Value::Boolean(value) => Value::Boolean(*value),
+            tf::Value::Array(values) => Value::Array(values.iter().map(Value::from).collect()),
+            tf::Value::Object(map) => Value::Object(values_from_map(map)),
+            tf::Value::Null => Value::Null,
+            tf::Value::Sensitive => Value::Sensitive,
+        }
+    }
+}
+
+fn values_from_map(map: &tf::ValueMap) -> BTreeMap<String, Value> {
+    map.iter()
+        .map(|(key, value)| (key.clone(), Value::from(value)))
+        .collect()
+}
+
+/// Difference between the values before and after the change
+#[derive(Serialize, Debug, PartialEq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Diff {
+    pub path: Vec<String>,
+    /// One of `added`, `removed`, `changed` and `unchanged`
+    pub kind: tf::DiffKind,
+    /// Only set for leaf values, nested objects and arrays are described by children
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Value>,
+    pub sensitive: bool,
+    /// The value is known only after apply
+    pub unknown: bool,
+    pub forces_replacement: bool,
+    pub ignored: bool,
+    pub cosmetic: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub children: Vec<Diff>,
+}
+
+impl From<&tf::DiffNode> for Diff {
+    fn from(node: &tf::DiffNode) -> Self {
+        Diff {
+            path: node.path.clone(),
+            kind: node.kind.clone(),
+            before: node.before.as_ref().map(Value::from),
+            after: node.after.as_ref().map(Value::from),
+            sensitive: node.sensitive,
+            unknown: node.unknown,
+            forces_replacement: node.forces_replacement,
+            ignored: node.ignored,
+            cosmetic: node.cosmetic,
+            description: node.description.clone(),
+            children: node.children.iter().map(Diff::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Change<'a> {
+    pub address: &'a str,
+    pub mode: &'a str,
+    #[serde(rename = "type")]
+    pub type_: &'a str,
+    pub name: &'a str,
+    /// One of `create`, `update`, `replace`, `delete`, `read`, `no-op` and `unknown`
+    pub action: &'a str,
+    /// Values with sensitive values masked, not set for created resources
+    pub before: Option<BTreeMap<String, Value>>,
+    /// Values with sensitive values masked, not set for deleted resources
+    pub after: Option<BTreeMap<String, Value>>,
+    pub diff: Vec<Diff>,
+    pub ignored_paths: &'a [Vec<String>],
+    /// Set for updates where all differences are ignored or between empty values
+    pub cosmetic: bool,
+    /// Only set when raw fields are included
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<&'a tf::RawResourceChange>,
+}
+
+/// Change counts keyed by the same action names as `Change::action`
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Counts {
+    pub create: usize,
+    pub update: usize,
+    pub replace: usize,
+    pub delete: usize,
+    pub read: usize,
+    #[serde(rename = "no-op")]
+    pub no_op: usize,
+    pub unknown: usize,
+}
+
+impl From<&tf::ActionCounts> for Counts {
+    fn from(counts: &tf::ActionCounts) -> Self {
+        Counts {
+            create: counts.create,
+            update: counts.update,
+            replace: counts.delete_create,
+            delete: counts.delete,
+            read: counts.read,
+            no_op: counts.no_op,
+            unknown: counts.unknown,
+        }
+    }
+}
+
+/// Resource counts of the Terraform plan summary line, replaced resources are added and destroyed
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Summary {
+    pub add: usize,
+    pub change: usize,
+    pub destroy: usize,
+}
+
+impl From<&tf::ActionCounts> for Summary {
+    fn from(counts: &tf::ActionCounts) -> Self {
+        Summary {
+            add: counts.add,
+            change: counts.change,
+            destroy: counts.destroy,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Plan<'a> {
+    /// Path of the plan file
+    pub path: &'a str,
+    pub unique_actions: Vec<&'a str>,
+    pub counts: Counts,
+    pub summary: Summary,
+    pub changes: Vec<Change<'a>>,
+    /// Only set when raw fields are included
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<&'a tf::RawPlan>,
+}
+
+/// Root of the output, plans are sorted by path
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Output<'a> {
+    pub schema_version: u32,
+    /// Counts of all plans
+    pub counts: Counts,
+    pub summary: Summary,
+    pub plans: Vec<Plan<'a>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JsonOptions {
+    pub pretty: bool,
+    /// Include the raw Terraform plan fields
+    pub include_raw: bool,
+}
+
+fn change_output<'a>(change: &'a tf::Change, options: &JsonOptions) -> Change<'a> {
+    Change {
+        address: &change.address,
+        mode: &change.mode,
+        type_: &change.type_,
+        name: &change.name,
+        action: change.action.as_str(),
+        before: change.before.as_ref().map(values_from_map),
+        after: change.after.as_ref().map(values_from_map),
+        diff: change.diff.iter().map(Diff::from).collect(),
+        ignored_paths: &change.ignored_paths,
+        cosmetic: change.cosmetic,
+        raw: options.include_raw.then_some(&change.raw),
+    }
+}
+
+#[must_use]
+pub fn render<'a>(data: &'a tf::Data, options: &JsonOptions) -> Output<'a> {
+    let plans = data
+        .plans
+        .iter()
+        .sorted_by_key(|(path, _)| *path)
+        .map(|(path, plan)| Plan {
+            path,
+            unique_actions: plan
+                .unique_actions()
+                .iter()
+                .map(tf::Action::as_str)
+                .collect(),
+            counts: Counts::from(plan.counts()),
+            summary: Summary::from(plan.counts()),
+            changes: plan
+                .changes()
+                .iter()
+                .map(|change| change_output(change, options))
+                .collect(),
+            raw: options.include_raw.then_some(plan.raw()),
+        })
+        .collect();
+
+    Output {
+        schema_version: SCHEMA_VERSION,
+        counts: Counts::from(&data.counts),
+        summary: Summary::from(&data.counts),
+        plans,
+    }
+}
+
+/// # Errors
+/// Returns an error if the output cannot be serialized
+pub fn render_json(data: &tf::Data, options: &JsonOptions) -> Result<String, types::Error> {
+    let output = render(data, options);
+    let result = if options.pretty {
+        serde_json::to_string_pretty(&output)
+    } else {
+        serde_json::to_string(&output)
+    };
+    result.map_err(|e| types::Error::chain("Failed to serialize output".to_string(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values() {
+        let value = tf::Value::Object(tf::ValueMap::from([
+            ("string".to_string(), tf::Value::String("foo".to_string())),
+            ("integer".to_string(), tf::Value::Integer(1)),
+            ("number".to_string(), tf::Value::Number("1e400".to_string())),
+            ("sensitive".to_string(), tf::Value::Sensitive),
+            (
+                "array".to_string(),
+                tf::Value::Array(vec![tf::Value::Null, tf::Value::Boolean(true)]),
+            ),
+        ]));
+
+        assert_eq!(
+            serde_json::to_string(&Value::from(&value)).unwrap(),
+            r#"{"type":"object","value":{"array":{"type":"array","value":[{"type":"null"},{"type":"boolean","value":true}]},"integer":{"type":"number","value":1},"number":{"type":"number","value":1e400},"sensitive":{"type":"sensitive"},"string":{"type":"string","value":"foo"}}}"#
+        );
+    }
+
+    #[test]
+    fn non_finite_floats() {
+        assert_eq!(
+            Value::from(&tf::Value::Float(f64::INFINITY)),
+            Value::String("inf".to_string())
+        );
+        assert_eq!(
+            Value::from(&tf::Value::Float(f64::NAN)),
+            Value::String("NaN".to_string())
+        );
+    }
+
+    #[test]
+    fn counts() {
+        let data = tf::tests::get_test_data();
+        let output = serde_json::to_value(render(&data, &JsonOptions::default())).unwrap();
+
+        assert_eq!(output["counts"]["replace"], 2);
+        assert_eq!(output["counts"]["no-op"], 1);
+        assert_eq!(output["counts"]["delete_create"], serde_json::Value::Null);
+        assert_eq!(output["summary"]["destroy"], 3);
+    }
+
+    #[test]
+    fn raw_excluded() {
+        let data = tf::tests::get_test_data();
+        let output = serde_json::to_value(render(&data, &JsonOptions::default())).unwrap();
+
+        assert_eq!(output["schema_version"], SCHEMA_VERSION);
+        assert_eq!(output["plans"][0]["raw"], serde_json::Value::Null);
+        assert_eq!(
+            output["plans"][0]["changes"][0]["raw"],
+            serde_json::Value::Null
+        );
+    }
+
+    #[test]
+    fn raw_included() {
+        let data = tf::tests::get_test_data();
+        let options = JsonOptions {
+            include_raw: true,
+            ..JsonOptions::default()
+        };
+        let output = serde_json::to_value(render(&data, &options)).unwrap();
+
+        assert!(output["plans"][0]["raw"].is_object());
+        assert!(output["plans"][0]["changes"][0]["raw"].is_object());
+    }
+
+    #[test]
+    fn pretty() {
+        let data = tf::tests::get_test_data();
+        let options = JsonOptions {
+            pretty: true,
+            ..JsonOptions::default()
+        };
+
+        assert!(render_json(&data, &options)
+            .unwrap()
+            .starts_with("{\n  \"schema_version\": 1,\n"));
+        assert!(render_json(&data, &JsonOptions::default())
+            .unwrap()
+            .starts_with("{\"schema_version\":1,"));
+    }
+}
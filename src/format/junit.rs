@@ -0,0 +1,181 @@
+use crate::schema;
+use crate::template::tera::{render_terraform_change, ValuesLimits};
+use crate::tf;
+use itertools::Itertools;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JunitOptions {
+    pub title: String,
+    /// Actions counted as failures, e.g. delete and replace
+    pub failure_actions: Vec<tf::Action>,
+    pub normalize_empty: bool,
+}
+
+/// Checks whether the character is allowed in XML 1.0 documents
+fn is_xml_char(char: char) -> bool {
+    matches!(char, '\t' | '\n' | '\r' | ' '..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+}
+
+/// Escapes text for XML elements, quotes are kept readable.
+/// Characters not allowed in XML are replaced with `�`
+#[must_use]
+pub fn escape_text(text: &str) -> String {
+    text.chars()
+        .map(|char| if is_xml_char(char) { char } else { '\u{FFFD}' })
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escapes text for XML attributes
+#[must_use]
+pub fn escape(text: &str) -> String {
+    escape_text(text)
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn testcase(
+    plan_key: &str,
+    change: &tf::Change,
+    schemas: &schema::Registry,
+    options: &JunitOptions,
+) -> Vec<String> {
+    let mut lines = vec![format!(
+        "    <testcase classname=\"{}\" name=\"{}\">",
+        escape(plan_key),
+        escape(&change.address)
+    )];
+    if options.failure_actions.contains(&change.action) {
+        lines.push(format!(
+            "      <failure message=\"{} of {}\" type=\"{}\"/>",
+            change.action.as_str(),
+            escape(&change.address),
+            change.action.as_str()
+        ));
+    }
+    let diff = render_terraform_change(
+        change,
+        schemas,
+        options.normalize_empty,
+        &ValuesLimits::default(),
+    );
+    lines.push(format!(
+        "      <system-out>{}</system-out>",
+        escape_text(&diff)
+    ));
+    lines.push("    </testcase>".to_string());
+    lines
+}
+
+fn failures(plan: &tf::Plan, options: &JunitOptions) -> usize {
+    plan.changes()
+        .iter()
+        .filter(|change| options.failure_actions.contains(&change.action))
+        .count()
+}
+
+/// Renders the report, plans are test suites and resource changes are test cases
+#[must_use]
+pub fn render(data: &tf::Data, options: &JunitOptions) -> String {
+    let plan_keys: Vec<&String> = data.plans.keys().sorted().collect();
+    let tests: usize = plan_keys
+        .iter()
+        .map(|plan_key| data.plans[*plan_key].changes().len())
+        .sum();
+    let failures_count: usize = plan_keys
+        .iter()
+        .map(|plan_key| failures(&data.plans[*plan_key], options))
+        .sum();
+
+    let mut lines = vec![
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>".to_string(),
+        format!(
+            "<testsuites name=\"{}\" tests=\"{tests}\" failures=\"{failures_count}\">",
+            escape(&options.title)
+        ),
+    ];
+    for plan_key in plan_keys {
+        let plan = &data.plans[plan_key];
+        lines.push(format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">",
+            escape(plan_key),
+            plan.changes().len(),
+            failures(plan, options)
+        ));
+        for change in plan.changes() {
+            lines.extend(testcase(plan_key, change, &data.schemas, options));
+        }
+        lines.push("  </testsuite>".to_string());
+    }
+    lines.push("</testsuites>".to_string());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_xml() {
+        assert_eq!(
+            escape(r#"a["b"]<c>&'d'"#),
+            "a[&quot;b&quot;]&lt;c&gt;&amp;&apos;d&apos;"
+        );
+        assert_eq!(escape_text(r#"a = "b" -> c"#), r#"a = "b" -&gt; c"#);
+    }
+
+    #[test]
+    fn escape_invalid_chars() {
+        assert_eq!(escape_text("a\u{0}b\u{8}\tc\n"), "a\u{FFFD}b\u{FFFD}\tc\n");
+        assert_eq!(
+            escape("\u{B}\u{C}\r\u{E}\u{1F}\u{FFFF}"),
+            "\u{FFFD}\u{FFFD}\r\u{FFFD}\u{FFFD}\u{FFFD}"
+        );
+    }
+
+    #[test]
+    fn failure() {
+        let plan = tf::tests::get_test_plan(&tf::tests::PlanType::Delete);
+        let options = JunitOptions {
+            failure_actions: vec![tf::Action::Delete],
+            ..JunitOptions::default()
+        };
+        let lines = testcase(
+            "plan",
+            &plan.changes()[0],
+            &schema::Registry::default(),
+            &options,
+        );
+
+        assert_eq!(
+            lines[0],
+            "    <testcase classname=\"plan\" name=\"terraform_data.foo-bar\">"
+        );
+        assert_eq!(
+            lines[1],
+            "      <failure message=\"delete of terraform_data.foo-bar\" type=\"delete\"/>"
+        );
+        assert!(
+            lines[2].starts_with("      <system-out>  # terraform_data.foo-bar will be destroyed")
+        );
+    }
+
+    #[test]
+    fn counts() {
+        let data = tf::tests::get_test_data();
+        let options = JunitOptions {
+            title: "Terraform plan".to_string(),
+            failure_actions: vec![tf::Action::Delete, tf::Action::DeleteCreate],
+            normalize_empty: false,
+        };
+        let result = render(&data, &options);
+
+        assert!(result.contains("<testsuites name=\"Terraform plan\" tests=\"6\" failures=\"3\">"));
+        let plan_key = tf::tests::get_test_plan_file(&tf::tests::PlanType::NoResources);
+        assert!(result.contains(&format!(
+            "<testsuite name=\"{plan_key}\" tests=\"0\" failures=\"0\">"
+        )));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::tf;
+
+pub mod adaptive_card;
+pub mod csv;
+pub mod html;
+pub mod json;
+pub mod junit;
+pub mod sarif;
+pub mod slack;
+pub mod terminal;
+
+/// Counts per action with the names used in summaries
+#[must_use]
+pub fn counts_by_action(counts: &tf::ActionCounts) -> [(tf::Action, usize, &'static str); 7] {
+    [
+        (tf::Action::Create, counts.create, "create"),
+        (tf::Action::Update, counts.update, "update"),
+        (tf::Action::DeleteCreate, counts.delete_create, "replace"),
+        (tf::Action::Delete, counts.delete, "destroy"),
+        (tf::Action::Read, counts.read, "read"),
+        (tf::Action::NoOp, counts.no_op, "no-op"),
+        (tf::Action::Unknown, counts.unknown, "unknown"),
+    ]
+}
+
+/// Changes with an effect on the infrastructure, no-op changes are skipped
+pub fn changed(plan: &tf::Plan) -> impl Iterator<Item = &tf::Change> {
+    plan.changes()
+        .iter()
+        .filter(|change| change.action != tf::Action::NoOp)
+}
+
+/// Class of a Terraform diff line from the sign in front of it, e.g. `add` for `+`,
+/// resource headers have their sign right-aligned
+#[must_use]
+pub fn diff_line_class(line: &str) -> Option<&'static str> {
+    match line.trim_start().split(' ').next() {
+        Some("+") => Some("add"),
+        Some("-") => Some("remove"),
+        Some("~") => Some("change"),
+        Some("-/+" | "+/-") => Some("replace"),
+        Some("<=") => Some("read"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_line_classes() {
+        assert_eq!(diff_line_class("+   input = \"foo\""), Some("add"));
+        assert_eq!(diff_line_class("-   id = \"a\" -> null"), Some("remove"));
+        assert_eq!(diff_line_class("~   triggers = {"), Some("change"));
+        assert_eq!(
+            diff_line_class("-/+ resource \"a\" \"b\" {"),
+            Some("replace")
+        );
+        assert_eq!(diff_line_class("  + resource \"a\" \"b\" {"), Some("add"));
+        assert_eq!(diff_line_class("  # a will be created"), None);
+        assert_eq!(diff_line_class("  }"), None);
+    }
+}
@@ -0,0 +1,393 @@
+use crate::tf;
+use crate::types;
+use itertools::Itertools;
+use serde::Serialize;
+
+pub const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+pub const VERSION: &str = "2.1.0";
+/// Base of the artifact URIs, code scanning resolves it to the repository root
+pub const SRCROOT: &str = "%SRCROOT%";
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Message {
+    pub text: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportingConfiguration {
+    pub level: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    pub id: String,
+    pub name: String,
+    pub short_description: Message,
+    pub default_configuration: ReportingConfiguration,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Driver {
+    pub name: String,
+    pub version: String,
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Tool {
+    pub driver: Driver,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactLocation {
+    pub uri: String,
+    /// Only set for URIs relative to the repository root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri_base_id: Option<String>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Region {
+    pub start_line: usize,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicalLocation {
+    pub artifact_location: ArtifactLocation,
+    pub region: Region,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LogicalLocation {
+    pub fully_qualified_name: String,
+    pub kind: String,
+}
+
+/// Plan file as the physical location, code scanning requires one, and the address as the logical one
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub physical_location: PhysicalLocation,
+    pub logical_locations: Vec<LogicalLocation>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Result {
+    pub rule_id: String,
+    pub rule_index: usize,
+    pub level: String,
+    pub message: Message,
+    pub locations: Vec<Location>,
+    /// Identifies the result across runs, code scanning uses it to track alerts
+    pub partial_fingerprints: std::collections::BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Run {
+    pub tool: Tool,
+    pub results: Vec<Result>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Log {
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    pub version: String,
+    pub runs: Vec<Run>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SarifOptions {
+    /// Actions reported as results
+    pub actions: Vec<tf::Action>,
+    /// Repository root, plan paths below it are reported relative to it
+    pub root: Option<std::path::PathBuf>,
+}
+
+impl Default for SarifOptions {
+    fn default() -> Self {
+        SarifOptions {
+            actions: vec![tf::Action::Delete, tf::Action::DeleteCreate],
+            root: None,
+        }
+    }
+}
+
+/// Percent-encodes a path segment of an URI, `:` is encoded as well as relative URIs
+/// can't have it in their first segment
+fn encode_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=@".contains(&byte) {
+                char::from(byte).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
+
+/// Plan path relative to the root with forward slashes as SARIF URIs require,
+/// paths outside of the root are reported as absolute file URIs
+#[must_use]
+pub fn artifact_location(plan_key: &str, root: Option<&std::path::Path>) -> ArtifactLocation {
+    let path = std::path::Path::new(plan_key);
+    let path = root
+        .and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path);
+    let path = path.strip_prefix(".").unwrap_or(path);
+    let uri = path
+        .components()
+        .filter(|component| !matches!(component, std::path::Component::RootDir))
+        .map(|component| encode_segment(&component.as_os_str().to_string_lossy()))
+        .join("/");
+    if path.has_root() {
+        ArtifactLocation {
+            uri: format!("file:///{uri}"),
+            uri_base_id: None,
+        }
+    } else {
+        ArtifactLocation {
+            uri,
+            uri_base_id: Some(SRCROOT.to_string()),
+        }
+    }
+}
+
+/// Severity level, destroying resources loses data while replacing them causes a downtime
+#[must_use]
+pub fn level(action: &tf::Action) -> &'static str {
+    match action {
+        tf::Action::Delete => "error",
+        tf::Action::DeleteCreate => "warning",
+        tf::Action::Create
+        | tf::Action::Read
+        | tf::Action::Update
+        | tf::Action::NoOp
+        | tf::Action::Unknown => "note",
+    }
+}
+
+/// Rule name and the verb used in messages
+fn description(action: &tf::Action) -> (&'static str, &'static str) {
+    match action {
+        tf::Action::Create => ("ResourceCreation", "created"),
+        tf::Action::DeleteCreate => ("ResourceReplacement", "replaced"),
+        tf::Action::Read => ("ResourceRead", "read"),
+        tf::Action::Update => ("ResourceUpdate", "updated"),
+        tf::Action::Delete => ("ResourceDeletion", "destroyed"),
+        tf::Action::NoOp => ("ResourceNoOp", "left unchanged"),
+        tf::Action::Unknown => ("ResourceUnknownAction", "changed by an unknown action"),
+    }
+}
+
+fn rule(action: &tf::Action) -> Rule {
+    let (name, verb) = description(action);
+    Rule {
+        id: action.as_str().to_string(),
+        name: name.to_string(),
+        short_description: Message {
+            text: format!("Resource will be {verb}"),
+        },
+        default_configuration: ReportingConfiguration {
+            level: level(action).to_string(),
+        },
+    }
+}
+
+fn result(artifact_location: ArtifactLocation, change: &tf::Change, rule_index: usize) -> Result {
+    let fingerprint = format!("{}:{}", artifact_location.uri, change.address);
+    let (_, verb) = description(&change.action);
+    Result {
+        rule_id: change.action.as_str().to_string(),
+        rule_index,
+        level: level(&change.action).to_string(),
+        message: Message {
+            text: format!("`{}` will be {verb}", change.address),
+        },
+        locations: vec![Location {
+            physical_location: PhysicalLocation {
+                artifact_location,
+                // Plans from `terraform show -json` are a single line
+                region: Region { start_line: 1 },
+            },
+            logical_locations: vec![LogicalLocation {
+                fully_qualified_name: change.address.clone(),
+                kind: "resource".to_string(),
+            }],
+        }],
+        partial_fingerprints: std::collections::BTreeMap::from([(
+            "resourceAddress/v1".to_string(),
+            fingerprint,
+        )]),
+    }
+}
+
+#[must_use]
+pub fn render(data: &tf::Data, options: &SarifOptions) -> Log {
+    let mut actions: Vec<&tf::Action> = Vec::new();
+    for action in &options.actions {
+        if !actions.contains(&action) {
+            actions.push(action);
+        }
+    }
+
+    let mut results = Vec::new();
+    for plan_key in data.plans.keys().sorted() {
+        for change in data.plans[plan_key].changes() {
+            if let Some(rule_index) = actions.iter().position(|action| **action == change.action) {
+                results.push(result(
+                    artifact_location(plan_key, options.root.as_deref()),
+                    change,
+                    rule_index,
+                ));
+            }
+        }
+    }
+
+    Log {
+        schema: SCHEMA.to_string(),
+        version: VERSION.to_string(),
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: env!("CARGO_PKG_NAME").to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                    rules: actions.into_iter().map(rule).collect(),
+                },
+            },
+            results,
+        }],
+    }
+}
+
+/// # Errors
+/// Returns an error if the log cannot be serialized
+pub fn render_json(
+    data: &tf::Data,
+    options: &SarifOptions,
+) -> std::result::Result<String, types::Error> {
+    serde_json::to_string(&render(data, options))
+        .map_err(|e| types::Error::chain("Failed to serialize log".to_string(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_default() {
+        let data = tf::tests::get_test_data();
+        let log = render(&data, &SarifOptions::default());
+
+        let rules: Vec<&str> = log.runs[0]
+            .tool
+            .driver
+            .rules
+            .iter()
+            .map(|rule| rule.id.as_str())
+            .collect();
+        assert_eq!(rules, vec!["delete", "replace"]);
+
+        let results: Vec<(&str, usize, &str)> = log.runs[0]
+            .results
+            .iter()
+            .map(|result| {
+                (
+                    result.rule_id.as_str(),
+                    result.rule_index,
+                    result.level.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                ("replace", 1, "warning"),
+                ("delete", 0, "error"),
+                ("replace", 1, "warning"),
+            ]
+        );
+    }
+
+    #[test]
+    fn location() {
+        let plan = tf::tests::get_test_plan(&tf::tests::PlanType::Delete);
+        let result = result(artifact_location("plan.json", None), &plan.changes()[0], 0);
+
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::json!({
+                "ruleId": "delete",
+                "ruleIndex": 0,
+                "level": "error",
+                "message": {"text": "`terraform_data.foo-bar` will be destroyed"},
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {"uri": "plan.json", "uriBaseId": "%SRCROOT%"},
+                        "region": {"startLine": 1}
+                    },
+                    "logicalLocations": [{
+                        "fullyQualifiedName": "terraform_data.foo-bar",
+                        "kind": "resource"
+                    }]
+                }],
+                "partialFingerprints": {"resourceAddress/v1": "plan.json:terraform_data.foo-bar"}
+            })
+        );
+    }
+
+    #[test]
+    fn relative_uri() {
+        let root = Some(std::path::Path::new("/repo"));
+        let uri = |plan_key: &str| {
+            let location = artifact_location(plan_key, root);
+            (location.uri, location.uri_base_id)
+        };
+        let srcroot = Some(SRCROOT.to_string());
+
+        assert_eq!(
+            uri("/repo/env/prod/plan.json"),
+            ("env/prod/plan.json".to_string(), srcroot.clone())
+        );
+        assert_eq!(
+            uri("./env/plan.json"),
+            ("env/plan.json".to_string(), srcroot.clone())
+        );
+        assert_eq!(
+            uri("/other/plan.json"),
+            ("file:///other/plan.json".to_string(), None)
+        );
+        assert_eq!(
+            uri("/repo/my env/plan #1.json"),
+            ("my%20env/plan%20%231.json".to_string(), srcroot.clone())
+        );
+        assert_eq!(
+            uri("/repo/prod:eu/100%/plän.json"),
+            ("prod%3Aeu/100%25/pl%C3%A4n.json".to_string(), srcroot)
+        );
+    }
+
+    #[test]
+    fn actions() {
+        let data = tf::tests::get_test_data();
+        let options = SarifOptions {
+            actions: vec![tf::Action::Update, tf::Action::Update],
+            ..SarifOptions::default()
+        };
+        let log = render(&data, &options);
+
+        assert_eq!(log.runs[0].tool.driver.rules.len(), 1);
+        assert_eq!(log.runs[0].results.len(), 1);
+        assert_eq!(log.runs[0].results[0].level, "note");
+    }
+}
@@ -0,0 +1,370 @@
+use crate::format;
+use crate::template::tera::{render_markdown_action, ActionSymbols};
+use crate::tf;
+use crate::types;
+use itertools::Itertools;
+use serde::Serialize;
+
+/// Slack rejects messages with more blocks
+pub const MAX_BLOCKS: usize = 50;
+/// Slack rejects header blocks with longer texts
+pub const MAX_HEADER_LENGTH: usize = 150;
+/// Slack rejects section blocks with longer texts
+pub const MAX_SECTION_LENGTH: usize = 3000;
+/// Plan keys and addresses are truncated to this length before escaping,
+/// so that sections are truncated at whole lines
+pub const MAX_CODE_LENGTH: usize = 500;
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Text {
+    PlainText { text: String },
+    Mrkdwn { text: String },
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Block {
+    Header { text: Text },
+    Section { text: Text },
+    Divider,
+    Context { elements: Vec<Text> },
+}
+
+/// Block Kit message payload
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Payload {
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlackOptions {
+    pub title: String,
+    /// Number of changed addresses listed per plan
+    pub max_changes: usize,
+    pub symbols: ActionSymbols,
+}
+
+impl Default for SlackOptions {
+    fn default() -> Self {
+        SlackOptions {
+            title: "Terraform plan".to_string(),
+            max_changes: 10,
+            symbols: ActionSymbols::Shortcodes,
+        }
+    }
+}
+
+/// Signs like `<=` are escaped, signs and words are code spans as `~` strikes text through
+fn render_action(action: &tf::Action, symbols: &ActionSymbols) -> String {
+    escape(&render_markdown_action(action, symbols))
+}
+
+/// Escapes the control characters of Slack mrkdwn
+#[must_use]
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Code span of the text, mrkdwn formatting like `*` or `_` isn't applied inside of it
+fn code(text: &str) -> String {
+    format!(
+        "`{}`",
+        escape(&truncate(&text.replace('`', "'"), MAX_CODE_LENGTH))
+    )
+}
+
+fn truncate(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+    let mut result: String = text.chars().take(max_length.saturating_sub(1)).collect();
+    result.push('…');
+    result
+}
+
+/// Keeps whole lines, escaped characters and code spans are never cut
+fn truncate_lines(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+    // Reserved for the ellipsis line
+    let budget = max_length - 2;
+    let mut result: Vec<&str> = Vec::new();
+    let mut size = 0;
+    for line in text.lines() {
+        let line_size = line.chars().count() + 1;
+        if size + line_size > budget {
+            break;
+        }
+        result.push(line);
+        size += line_size;
+    }
+    result.push("…");
+    result.join("\n")
+}
+
+fn section(text: &str) -> Block {
+    Block::Section {
+        text: Text::Mrkdwn {
+            text: truncate_lines(text, MAX_SECTION_LENGTH),
+        },
+    }
+}
+
+fn context(text: String) -> Block {
+    Block::Context {
+        elements: vec![Text::Mrkdwn { text }],
+    }
+}
+
+fn counts_text(counts: &tf::ActionCounts, symbols: &ActionSymbols) -> String {
+    let result = format::counts_by_action(counts)
+        .iter()
+        .filter(|(_, count, _)| *count > 0)
+        .map(|(action, count, noun)| format!("{}{count} {noun}", render_action(action, symbols)))
+        .join("  ");
+    if result.is_empty() {
+        return "No resource changes".to_string();
+    }
+    result
+}
+
+/// Joins whole lines up to the section limit, the hidden lines are counted in the notice
+fn changes_text(lines: &[String], hidden: usize) -> String {
+    let notice = |count: usize| format!("_… and {count} more change{}_", plural(count));
+    let budget = MAX_SECTION_LENGTH - notice(lines.len() + hidden).chars().count() - 1;
+
+    let mut result: Vec<&str> = Vec::new();
+    let mut size = 0;
+    for line in lines {
+        let line_size = line.chars().count() + 1;
+        if size + line_size > budget && !result.is_empty() {
+            break;
+        }
+        result.push(line);
+        size += line_size;
+    }
+    let hidden = hidden + lines.len() - result.len();
+    let mut text = result.join("\n");
+    if hidden > 0 {
+        text = format!("{text}\n{}", notice(hidden));
+    }
+    text
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+fn plan_blocks(plan_key: &str, plan: &tf::Plan, options: &SlackOptions) -> Vec<Block> {
+    let actions: String = plan
+        .unique_actions()
+        .iter()
+        .map(|action| render_action(action, &options.symbols))
+        .collect();
+    let mut blocks = vec![
+        Block::Divider,
+        section(&format!(
+            "{actions}{}\n{}",
+            code(plan_key),
+            counts_text(plan.counts(), &options.symbols)
+        )),
+    ];
+
+    let changed: Vec<&tf::Change> = format::changed(plan).collect();
+    if changed.is_empty() {
+        return blocks;
+    }
+    let lines: Vec<String> = changed
+        .iter()
+        .take(options.max_changes)
+        .map(|change| {
+            format!(
+                "{}{}",
+                render_action(&change.action, &options.symbols),
+                code(&change.address)
+            )
+        })
+        .collect();
+    let hidden = changed.len() - lines.len();
+    blocks.push(section(&changes_text(&lines, hidden)));
+    blocks
+}
+
+#[must_use]
+pub fn render(data: &tf::Data, options: &SlackOptions) -> Payload {
+    let mut blocks = vec![
+        Block::Header {
+            text: Text::PlainText {
+                text: truncate(&options.title, MAX_HEADER_LENGTH),
+            },
+        },
+        section(&format!(
+            "*Plan:* {} to add, {} to change, {} to destroy.",
+            data.counts.add, data.counts.change, data.counts.destroy
+        )),
+    ];
+
+    let plan_keys: Vec<&String> = data.plans.keys().sorted().collect();
+    for (index, plan_key) in plan_keys.iter().enumerate() {
+        let plan_blocks = plan_blocks(plan_key, &data.plans[*plan_key], options);
+        // One block is kept for the truncation notice
+        if blocks.len() + plan_blocks.len() >= MAX_BLOCKS {
+            let hidden = plan_keys.len() - index;
+            blocks.push(context(format!(
+                "_{hidden} more plan{} not shown_",
+                plural(hidden)
+            )));
+            break;
+        }
+        blocks.extend(plan_blocks);
+    }
+
+    Payload { blocks }
+}
+
+/// # Errors
+/// Returns an error if the payload cannot be serialized
+pub fn render_json(data: &tf::Data, options: &SlackOptions) -> Result<String, types::Error> {
+    serde_json::to_string(&render(data, options))
+        .map_err(|e| types::Error::chain("Failed to serialize payload".to_string(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_mrkdwn() {
+        assert_eq!(escape("a<b>&c"), "a&lt;b&gt;&amp;c");
+    }
+
+    #[test]
+    fn code_span() {
+        assert_eq!(code("plans/*a_b*/~c`d`<e>"), "`plans/*a_b*/~c'd'&lt;e&gt;`");
+        let text = code(&"&".repeat(1000));
+        assert!(text.ends_with("&amp;…`"));
+        assert_eq!(text.matches("&amp;").count(), MAX_CODE_LENGTH - 1);
+    }
+
+    #[test]
+    fn counts() {
+        let counts = tf::ActionCounts::from_actions(&[
+            tf::Action::Create,
+            tf::Action::Create,
+            tf::Action::Delete,
+        ]);
+        assert_eq!(
+            counts_text(&counts, &ActionSymbols::Shortcodes),
+            ":white_check_mark: 2 create  :x: 1 destroy"
+        );
+        assert_eq!(
+            counts_text(&counts, &ActionSymbols::Ascii),
+            "`+` 2 create  `-` 1 destroy"
+        );
+        assert_eq!(
+            counts_text(&tf::ActionCounts::default(), &ActionSymbols::Shortcodes),
+            "No resource changes"
+        );
+    }
+
+    #[test]
+    fn escaped_symbols() {
+        assert_eq!(
+            render_action(&tf::Action::Read, &ActionSymbols::Ascii),
+            "`&lt;=` "
+        );
+    }
+
+    #[test]
+    fn render_default() {
+        let data = tf::tests::get_test_data();
+        let payload = render(&data, &SlackOptions::default());
+
+        assert_eq!(
+            payload.blocks[0],
+            Block::Header {
+                text: Text::PlainText {
+                    text: "Terraform plan".to_string()
+                }
+            }
+        );
+        assert_eq!(
+            payload.blocks[1],
+            section("*Plan:* 3 to add, 1 to change, 3 to destroy.")
+        );
+        assert_eq!(payload.blocks.len(), 2 + 7 * 2 + 5);
+    }
+
+    #[test]
+    fn max_changes() {
+        let lines: Vec<String> = (0..3).map(|index| format!("`test.{index}`")).collect();
+        assert_eq!(
+            changes_text(&lines, 2),
+            "`test.0`\n`test.1`\n`test.2`\n_… and 2 more changes_"
+        );
+    }
+
+    #[test]
+    fn section_limit() {
+        let lines: Vec<String> = (0..1000).map(|index| format!("`test.{index}`")).collect();
+        let text = changes_text(&lines, 0);
+
+        assert!(text.chars().count() <= MAX_SECTION_LENGTH);
+        assert!(text.ends_with("more changes_"));
+    }
+
+    #[test]
+    fn section_whole_lines() {
+        let text = format!("{}\n{}", "&amp;".repeat(500), "&amp;".repeat(500));
+        let Block::Section {
+            text: Text::Mrkdwn { text },
+        } = section(&text)
+        else {
+            panic!("section expected");
+        };
+
+        assert_eq!(text, format!("{}\n…", "&amp;".repeat(500)));
+    }
+
+    #[test]
+    fn block_limit() {
+        let mut data = tf::tests::get_test_data();
+        let plan_key = tf::tests::get_test_plan_file(&tf::tests::PlanType::Create);
+        for index in 0..100 {
+            data.plans.insert(
+                format!("{plan_key}{index}"),
+                tf::tests::get_test_plan(&tf::tests::PlanType::Create),
+            );
+        }
+        let payload = render(&data, &SlackOptions::default());
+
+        assert!(payload.blocks.len() <= MAX_BLOCKS);
+        assert!(matches!(payload.blocks.last(), Some(Block::Context { .. })));
+    }
+
+    #[test]
+    fn header_limit() {
+        let data = tf::tests::get_test_data();
+        let options = SlackOptions {
+            title: "a".repeat(200),
+            ..SlackOptions::default()
+        };
+        let payload = render(&data, &options);
+
+        let Block::Header {
+            text: Text::PlainText { text },
+        } = &payload.blocks[0]
+        else {
+            panic!("header expected");
+        };
+        assert_eq!(text.chars().count(), MAX_HEADER_LENGTH);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::format;
+use crate::schema;
+use crate::template::tera::{render_terraform_change, ValuesLimits};
+use crate::tf;
+use crate::types;
+use itertools::Itertools;
+use std::str::FromStr;
+
+const BOLD: &str = "1";
+const DIM: &str = "2";
+const RED: &str = "31";
+const GREEN: &str = "32";
+const YELLOW: &str = "33";
+const MAGENTA: &str = "35";
+const CYAN: &str = "36";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ColorChoice {
+    /// Colors when writing to a terminal and `NO_COLOR` is not set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = types::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(types::Error::default(format!("Invalid color: {s}"))),
+        }
+    }
+}
+
+impl ColorChoice {
+    /// Resolves the choice, a non-empty `NO_COLOR` disables colors as described on <https://no-color.org>
+    #[must_use]
+    pub fn enabled(&self, is_terminal: bool, no_color: Option<&str>) -> bool {
+        match self {
+            ColorChoice::Auto => is_terminal && no_color.is_none_or(str::is_empty),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TerminalOptions {
+    pub color: bool,
+    pub normalize_empty: bool,
+}
+
+fn paint(text: &str, code: &str, options: &TerminalOptions) -> String {
+    if options.color {
+        format!("\x1b[{code}m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Colors diff lines by their sign, lines without a sign are unchanged values and dimmed
+fn render_diff(
+    change: &tf::Change,
+    schemas: &schema::Registry,
+    options: &TerminalOptions,
+) -> Vec<String> {
+    render_terraform_change(
+        change,
+        schemas,
+        options.normalize_empty,
+        &ValuesLimits::default(),
+    )
+    .lines()
+    .map(|line| {
+        let code = match format::diff_line_class(line) {
+            Some("add") => GREEN,
+            Some("remove") => RED,
+            Some("change") => YELLOW,
+            Some("replace") => MAGENTA,
+            Some("read") => CYAN,
+            _ if line.starts_with("  # ") => BOLD,
+            _ => DIM,
+        };
+        paint(line, code, options)
+    })
+    .collect()
+}
+
+fn summary(counts: &tf::ActionCounts) -> String {
+    format!(
+        "{} to add, {} to change, {} to destroy.",
+        counts.add, counts.change, counts.destroy
+    )
+}
+
+/// Renders the changes like `terraform plan`, no-op changes are skipped
+#[must_use]
+pub fn render(data: &tf::Data, options: &TerminalOptions) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for plan_key in data.plans.keys().sorted() {
+        let plan = &data.plans[plan_key];
+        lines.push(paint(plan_key, BOLD, options));
+        lines.push(String::new());
+
+        let changed: Vec<&tf::Change> = format::changed(plan).collect();
+        if changed.is_empty() {
+            lines.push("No resource changes".to_string());
+            lines.push(String::new());
+        }
+        for change in changed {
+            lines.extend(render_diff(change, &data.schemas, options));
+            lines.push(String::new());
+        }
+        lines.push(format!(
+            "{} {}",
+            paint("Plan:", BOLD, options),
+            summary(plan.counts())
+        ));
+        lines.push(String::new());
+    }
+    lines.push(format!(
+        "{} {}",
+        paint("Total:", BOLD, options),
+        summary(&data.counts)
+    ));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_choice() {
+        assert!(ColorChoice::Auto.enabled(true, None));
+        assert!(ColorChoice::Auto.enabled(true, Some("")));
+        assert!(!ColorChoice::Auto.enabled(true, Some("1")));
+        assert!(!ColorChoice::Auto.enabled(false, None));
+        assert!(ColorChoice::Always.enabled(false, Some("1")));
+        assert!(!ColorChoice::Never.enabled(true, None));
+        assert!(ColorChoice::from_str("invalid").is_err());
+    }
+
+    #[test]
+    fn colors() {
+        let plan = tf::tests::get_test_plan(&tf::tests::PlanType::DeleteCreate);
+        let options = TerminalOptions {
+            color: true,
+            ..TerminalOptions::default()
+        };
+        let lines = render_diff(&plan.changes()[0], &schema::Registry::default(), &options);
+
+        assert_eq!(
+            lines,
+            vec![
+                "\x1b[1m  # null_resource.foo-bar must be replaced\x1b[0m",
+                "\x1b[35m-/+ resource \"null_resource\" \"foo-bar\" {\x1b[0m",
+                "\x1b[33m~     id       = \"4525788878524015586\" -> (known after apply)\x1b[0m",
+                "\x1b[33m~     triggers = { # forces replacement\x1b[0m",
+                "\x1b[33m~       always_run = \"2024-10-25T21:40:19Z\" -> (known after apply)\x1b[0m",
+                "\x1b[2m      }\x1b[0m",
+                "\x1b[2m    }\x1b[0m",
+            ]
+        );
+    }
+
+    #[test]
+    fn no_color() {
+        let plan = tf::tests::get_test_plan(&tf::tests::PlanType::Create);
+        let lines = render_diff(
+            &plan.changes()[0],
+            &schema::Registry::default(),
+            &TerminalOptions::default(),
+        );
+
+        assert!(lines.iter().all(|line| !line.contains('\x1b')));
+    }
+}
@@ -1,4 +1,7 @@
 pub mod cli;
+pub mod config;
+pub mod format;
+pub mod schema;
 pub mod template;
 pub mod tf;
 pub mod types;
@@ -0,0 +1,397 @@
+use crate::types;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum RawNestingMode {
+    Single,
+    Group,
+    List,
+    Set,
+    Map,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RawNestedType {
+    #[serde(default)]
+    pub attributes: HashMap<String, RawAttribute>,
+    pub nesting_mode: RawNestingMode,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RawAttribute {
+    #[serde(rename = "type")]
+    pub type_: Option<serde_json::Value>,
+    pub nested_type: Option<RawNestedType>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub sensitive: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RawBlockType {
+    pub nesting_mode: RawNestingMode,
+    pub block: RawBlock,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RawBlock {
+    #[serde(default)]
+    pub attributes: HashMap<String, RawAttribute>,
+    #[serde(default)]
+    pub block_types: HashMap<String, RawBlockType>,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RawSchema {
+    pub block: RawBlock,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RawProviderSchema {
+    #[serde(default)]
+    pub resource_schemas: HashMap<String, RawSchema>,
+    #[serde(default)]
+    pub data_source_schemas: HashMap<String, RawSchema>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RawProviderSchemas {
+    // format_version: String,
+    #[serde(default)]
+    pub provider_schemas: HashMap<String, RawProviderSchema>,
+}
+
+impl FromStr for RawProviderSchemas {
+    type Err = types::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match serde_json::from_str::<RawProviderSchemas>(s) {
+            Ok(schemas) => Ok(schemas),
+            Err(e) => Err(types::Error::chain(
+                "Failed to parse provider schemas".to_string(),
+                e,
+            )),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    Attribute,
+    Block,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum Collection {
+    /// Primitive values, objects and single nested blocks
+    Single,
+    List,
+    Set,
+    Map,
+}
+
+/// Schema of a value, nested values are described either by `children` for values with
+/// known keys or by `element` for lists, sets and maps
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct SchemaNode {
+    pub kind: NodeKind,
+    pub collection: Collection,
+    pub sensitive: bool,
+    pub description: Option<String>,
+    pub children: HashMap<String, SchemaNode>,
+    pub element: Option<Box<SchemaNode>>,
+}
+
+impl SchemaNode {
+    fn new(kind: NodeKind, collection: Collection) -> Self {
+        SchemaNode {
+            kind,
+            collection,
+            sensitive: false,
+            description: None,
+            children: HashMap::new(),
+            element: None,
+        }
+    }
+
+    fn from_type(type_: &serde_json::Value) -> Self {
+        let mut node = SchemaNode::new(NodeKind::Attribute, Collection::Single);
+        let serde_json::Value::Array(parts) = type_ else {
+            return node;
+        };
+        match (parts.first().and_then(|kind| kind.as_str()), parts.get(1)) {
+            (Some("list"), Some(element)) => {
+                node.collection = Collection::List;
+                node.element = Some(Box::new(SchemaNode::from_type(element)));
+            }
+            (Some("set"), Some(element)) => {
+                node.collection = Collection::Set;
+                node.element = Some(Box::new(SchemaNode::from_type(element)));
+            }
+            (Some("map"), Some(element)) => {
+                node.collection = Collection::Map;
+                node.element = Some(Box::new(SchemaNode::from_type(element)));
+            }
+            (Some("object"), Some(serde_json::Value::Object(attributes))) => {
+                for (key, attribute) in attributes {
+                    node.children
+                        .insert(key.clone(), SchemaNode::from_type(attribute));
+                }
+            }
+            (Some("tuple"), Some(serde_json::Value::Array(elements))) => {
+                for (index, element) in elements.iter().enumerate() {
+                    node.children
+                        .insert(index.to_string(), SchemaNode::from_type(element));
+                }
+            }
+            _ => {}
+        }
+        node
+    }
+
+    fn from_nesting(kind: NodeKind, nesting_mode: &RawNestingMode, object: SchemaNode) -> Self {
+        let collection = match nesting_mode {
+            RawNestingMode::Single | RawNestingMode::Group => return object,
+            RawNestingMode::List => Collection::List,
+            RawNestingMode::Set => Collection::Set,
+            RawNestingMode::Map => Collection::Map,
+        };
+        let mut node = SchemaNode::new(kind, collection);
+        node.element = Some(Box::new(object));
+        node
+    }
+
+    fn from_attributes(attributes: &HashMap<String, RawAttribute>) -> HashMap<String, SchemaNode> {
+        attributes
+            .iter()
+            .map(|(key, attribute)| (key.clone(), SchemaNode::from_raw_attribute(attribute)))
+            .collect()
+    }
+
+    fn from_raw_attribute(raw: &RawAttribute) -> Self {
+        let mut node = match (&raw.nested_type, &raw.type_) {
+            (Some(nested_type), _) => {
+                let mut object = SchemaNode::new(NodeKind::Attribute, Collection::Single);
+                object.children = SchemaNode::from_attributes(&nested_type.attributes);
+                SchemaNode::from_nesting(NodeKind::Attribute, &nested_type.nesting_mode, object)
+            }
+            (None, Some(type_)) => SchemaNode::from_type(type_),
+            (None, None) => SchemaNode::new(NodeKind::Attribute, Collection::Single),
+        };
+        node.sensitive = raw.sensitive;
+        node.description.clone_from(&raw.description);
+        node
+    }
+
+    fn from_raw_block(raw: &RawBlock) -> Self {
+        let mut node = SchemaNode::new(NodeKind::Block, Collection::Single);
+        node.description.clone_from(&raw.description);
+        node.children = SchemaNode::from_attributes(&raw.attributes);
+        for (key, block_type) in &raw.block_types {
+            let block = SchemaNode::from_raw_block(&block_type.block);
+            node.children.insert(
+                key.clone(),
+                SchemaNode::from_nesting(NodeKind::Block, &block_type.nesting_mode, block),
+            );
+        }
+        node
+    }
+
+    /// Returns the schema of a nested value, `step` is an attribute name, a map key or an index
+    #[must_use]
+    pub fn child(&self, step: &str) -> Option<&SchemaNode> {
+        match &self.element {
+            Some(element) => Some(element),
+            None => self.children.get(step),
+        }
+    }
+
+    #[must_use]
+    pub fn find(&self, path: &[String]) -> Option<&SchemaNode> {
+        let mut node = self;
+        for step in path {
+            node = node.child(step)?;
+        }
+        Some(node)
+    }
+
+    #[must_use]
+    pub fn is_set(&self) -> bool {
+        self.collection == Collection::Set
+    }
+
+    #[must_use]
+    pub fn is_block(&self) -> bool {
+        self.kind == NodeKind::Block
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct Registry {
+    pub resources: HashMap<String, SchemaNode>,
+    pub data_sources: HashMap<String, SchemaNode>,
+}
+
+impl Registry {
+    #[must_use]
+    pub fn from_raw(raw: &RawProviderSchemas) -> Self {
+        let mut registry = Registry::default();
+        for provider in raw.provider_schemas.values() {
+            for (type_, schema) in &provider.resource_schemas {
+                registry
+                    .resources
+                    .insert(type_.clone(), SchemaNode::from_raw_block(&schema.block));
+            }
+            for (type_, schema) in &provider.data_source_schemas {
+                registry
+                    .data_sources
+                    .insert(type_.clone(), SchemaNode::from_raw_block(&schema.block));
+            }
+        }
+        registry
+    }
+
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed
+    pub fn from_file(path: &str) -> Result<Self, types::Error> {
+        let raw_file = std::fs::read_to_string(path)
+            .map_err(|e| types::Error::chain(format!("Failed to read file({path})"), e))?;
+        let raw = RawProviderSchemas::from_str(&raw_file)
+            .map_err(|e| types::Error::chain(format!("Failed to parse file({path})"), e))?;
+        Ok(Registry::from_raw(&raw))
+    }
+
+    /// Returns the schema of a resource, `mode` is either `managed` or `data`
+    #[must_use]
+    pub fn get(&self, mode: &str, type_: &str) -> Option<&SchemaNode> {
+        match mode {
+            "data" => self.data_sources.get(type_),
+            _ => self.resources.get(type_),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils;
+
+    fn get_test_registry() -> Registry {
+        let path = utils::test::get_test_data_file_path("schemas/providers.json");
+        Registry::from_file(&path).unwrap()
+    }
+
+    fn path(path: &str) -> Vec<String> {
+        path.split('.').map(ToString::to_string).collect()
+    }
+
+    mod registry {
+        use super::*;
+
+        #[test]
+        fn get() {
+            let registry = get_test_registry();
+
+            assert!(registry.get("managed", "test_type").is_some());
+            assert!(registry.get("data", "test_type").is_some());
+            assert!(registry.get("managed", "unknown_type").is_none());
+        }
+
+        #[test]
+        fn invalid_path() {
+            let registry = Registry::from_file("invalid path");
+            assert_eq!(
+                registry.unwrap_err().full_message(),
+                "Failed to read file(invalid path). No such file or directory (os error 2)"
+            );
+        }
+
+        #[test]
+        fn invalid_json() {
+            let registry = RawProviderSchemas::from_str("invalid json");
+            assert_eq!(
+                registry.unwrap_err().full_message(),
+                "Failed to parse provider schemas. expected value at line 1 column 1"
+            );
+        }
+    }
+
+    mod schema_node {
+        use super::*;
+
+        #[test]
+        fn attribute() {
+            let registry = get_test_registry();
+            let schema = registry.get("managed", "test_type").unwrap();
+
+            let node = schema.find(&path("password")).unwrap();
+            assert_eq!(node.kind, NodeKind::Attribute);
+            assert!(node.sensitive);
+            assert_eq!(
+                node.description,
+                Some("Password of the resource".to_string())
+            );
+        }
+
+        #[test]
+        fn collections() {
+            let registry = get_test_registry();
+            let schema = registry.get("managed", "test_type").unwrap();
+
+            assert!(schema.find(&path("security_groups")).unwrap().is_set());
+            assert_eq!(
+                schema.find(&path("tags")).unwrap().collection,
+                Collection::Map
+            );
+            assert_eq!(
+                schema.find(&path("tags.Name")).unwrap().collection,
+                Collection::Single
+            );
+            assert!(schema.find(&path("settings.nested.0")).is_some());
+        }
+
+        #[test]
+        fn blocks() {
+            let registry = get_test_registry();
+            let schema = registry.get("managed", "test_type").unwrap();
+
+            let ingress = schema.find(&path("ingress")).unwrap();
+            assert!(ingress.is_block());
+            assert!(ingress.is_set());
+
+            let port = schema.find(&path("ingress.0.port")).unwrap();
+            assert_eq!(port.kind, NodeKind::Attribute);
+            assert_eq!(port.description, Some("Port number".to_string()));
+
+            let timeouts = schema.find(&path("timeouts")).unwrap();
+            assert!(timeouts.is_block());
+            assert_eq!(timeouts.collection, Collection::Single);
+        }
+
+        #[test]
+        fn nested_type() {
+            let registry = get_test_registry();
+            let schema = registry.get("managed", "test_type").unwrap();
+
+            let endpoints = schema.find(&path("endpoints")).unwrap();
+            assert_eq!(endpoints.kind, NodeKind::Attribute);
+            assert_eq!(endpoints.collection, Collection::List);
+            assert!(schema.find(&path("endpoints.1.token")).unwrap().sensitive);
+        }
+
+        #[test]
+        fn unknown() {
+            let registry = get_test_registry();
+            let schema = registry.get("managed", "test_type").unwrap();
+
+            assert!(schema.find(&path("unknown")).is_none());
+            assert!(schema.find(&path("password.unknown")).is_none());
+        }
+    }
+}
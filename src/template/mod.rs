@@ -2,6 +2,7 @@ use crate::tf;
 use crate::types;
 use std::str::FromStr;
 
+pub mod split;
 pub mod tera;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -22,24 +23,131 @@ impl FromStr for Engine {
     }
 }
 
+/// Renders the template, `render_action` and `render_actions` default to the symbols,
+/// which are available as `options.symbols` as well
+///
 /// # Errors
 /// Returns an error if rendering fails
-pub fn render(engine: &Engine, data: &tf::Data, template: &str) -> Result<String, types::Error> {
+pub fn render(
+    engine: &Engine,
+    data: &tf::Data,
+    template: &str,
+    symbols: &tera::ActionSymbols,
+) -> Result<String, types::Error> {
     match engine {
-        Engine::Tera => tera::render(data, template, None),
+        Engine::Tera => {
+            let mut options = tera::RenderOptions::new();
+            options.insert(
+                "symbols".to_string(),
+                tera::RenderOptionValue::String(symbols.as_str().to_string()),
+            );
+            tera::render(data, template, Some(options))
+        }
     }
 }
 
-/// # Errors
-/// Returns an error if rendering fails
-pub fn render_github(data: &tf::Data, show_changed_values: bool) -> Result<String, types::Error> {
-    let template = tera::GITHUB_MARKDOWN_TEMPLATE;
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GithubOptions {
+    pub show_changed_values: bool,
+    pub style: tera::ValuesStyle,
+    pub normalize_empty: bool,
+    pub limits: tera::ValuesLimits,
+    pub summary: bool,
+    pub symbols: tera::ActionSymbols,
+}
+
+impl GithubOptions {
+    /// GitLab renders the Github markdown, emoji are replaced with shortcodes
+    /// and values are rendered as diffs for highlighting
+    #[must_use]
+    pub fn gitlab() -> Self {
+        GithubOptions {
+            style: tera::ValuesStyle::Terraform,
+            symbols: tera::ActionSymbols::Shortcodes,
+            ..GithubOptions::default()
+        }
+    }
+}
+
+fn github_render_options(github_options: &GithubOptions) -> tera::RenderOptions {
     let mut options = tera::RenderOptions::new();
     options.insert(
         "show_changed_values".to_string(),
-        tera::RenderOptionValue::Bool(show_changed_values),
+        tera::RenderOptionValue::Bool(github_options.show_changed_values),
+    );
+    options.insert(
+        "style".to_string(),
+        tera::RenderOptionValue::String(github_options.style.as_str().to_string()),
+    );
+    options.insert(
+        "normalize_empty".to_string(),
+        tera::RenderOptionValue::Bool(github_options.normalize_empty),
     );
-    tera::render(data, template, Some(options))
+    options.insert(
+        "summary".to_string(),
+        tera::RenderOptionValue::Bool(github_options.summary),
+    );
+    let limits = [
+        ("max_string_length", github_options.limits.max_string_length),
+        ("max_lines", github_options.limits.max_lines),
+        ("max_depth", github_options.limits.max_depth),
+        ("max_elements", github_options.limits.max_elements),
+    ];
+    for (key, limit) in limits {
+        if let Some(limit) = limit {
+            options.insert(key.to_string(), tera::RenderOptionValue::Integer(limit));
+        }
+    }
+    options.insert(
+        "symbols".to_string(),
+        tera::RenderOptionValue::String(github_options.symbols.as_str().to_string()),
+    );
+    options
+}
+
+/// # Errors
+/// Returns an error if rendering fails
+pub fn render_github(
+    data: &tf::Data,
+    github_options: &GithubOptions,
+) -> Result<String, types::Error> {
+    let template = tera::GITHUB_MARKDOWN_TEMPLATE;
+    tera::render(data, template, Some(github_render_options(github_options)))
+}
+
+/// Renders Github markdown into parts of at most `max_size` characters.
+/// Unchanged values are dropped first, then changes are reduced to their addresses,
+/// only then the output is split into numbered parts.
+///
+/// # Errors
+/// Returns an error if rendering fails or the summary doesn't fit into the first part
+pub fn render_github_parts(
+    data: &tf::Data,
+    github_options: &GithubOptions,
+    max_size: usize,
+) -> Result<Vec<String>, types::Error> {
+    let result = render_github(data, github_options)?;
+    if split::fits(&result, max_size) {
+        return Ok(vec![result]);
+    }
+
+    if github_options.show_changed_values {
+        let reduced_options = GithubOptions {
+            show_changed_values: false,
+            ..github_options.clone()
+        };
+        let result = render_github(data, &reduced_options)?;
+        if split::fits(&result, max_size) {
+            return Ok(vec![result]);
+        }
+    }
+
+    let result = tera::render(
+        data,
+        tera::GITHUB_ADDRESSES_TEMPLATE,
+        Some(github_render_options(github_options)),
+    )?;
+    split::split(&result, max_size)
 }
 
 #[cfg(test)]
@@ -69,12 +177,44 @@ mod tests {
         fn default() {
             let data = tf::tests::get_test_data();
             let template = utils::test::get_test_data_file_contents("tera/templates/custom");
-            let result = render(&Engine::Tera, &data, &template).unwrap();
+            let result = render(
+                &Engine::Tera,
+                &data,
+                &template,
+                &tera::ActionSymbols::default(),
+            )
+            .unwrap();
 
             let expected = utils::test::get_test_data_file_contents("tera/renders/custom.md");
 
             pretty_assertions::assert_eq!(expected, result);
         }
+
+        #[test]
+        fn diff() {
+            let data = tf::tests::get_test_data();
+            let template = utils::test::get_test_data_file_contents("tera/templates/diff");
+            let result = render(
+                &Engine::Tera,
+                &data,
+                &template,
+                &tera::ActionSymbols::default(),
+            )
+            .unwrap();
+
+            let expected = utils::test::get_test_data_file_contents("tera/renders/diff.md");
+
+            pretty_assertions::assert_eq!(expected, result);
+        }
+
+        #[test]
+        fn symbols() {
+            let data = tf::tests::get_test_data();
+            let template = "{{ render_action(action='Create') }}{{ options.symbols }}";
+            let result = render(&Engine::Tera, &data, template, &tera::ActionSymbols::Ascii);
+
+            assert_eq!(result.unwrap(), "+ ascii");
+        }
     }
 
     mod render_github {
@@ -84,12 +224,145 @@ mod tests {
         #[test]
         fn default() {
             let data = tf::tests::get_test_data();
-            let result = render_github(&data, false).unwrap();
+            let result = render_github(&data, &GithubOptions::default()).unwrap();
 
             let expected =
                 utils::test::get_test_data_file_contents("tera/renders/github_markdown/default.md");
 
             pretty_assertions::assert_eq!(expected, result);
         }
+
+        #[test]
+        fn terraform() {
+            let data = tf::tests::get_test_data();
+            let result = render_github(
+                &data,
+                &GithubOptions {
+                    style: tera::ValuesStyle::Terraform,
+                    ..GithubOptions::default()
+                },
+            )
+            .unwrap();
+
+            let expected = utils::test::get_test_data_file_contents(
+                "tera/renders/github_markdown/terraform.md",
+            );
+
+            pretty_assertions::assert_eq!(expected, result);
+        }
+
+        #[test]
+        fn summary() {
+            let data = tf::tests::get_test_data();
+            let result = render_github(
+                &data,
+                &GithubOptions {
+                    summary: true,
+                    ..GithubOptions::default()
+                },
+            )
+            .unwrap();
+
+            let expected =
+                utils::test::get_test_data_file_contents("tera/renders/github_markdown/summary.md");
+
+            pretty_assertions::assert_eq!(expected, result);
+        }
+    }
+
+    mod gitlab {
+        use super::*;
+        use crate::utils;
+
+        #[test]
+        fn default() {
+            let data = tf::tests::get_test_data();
+            let result = render_github(&data, &GithubOptions::gitlab()).unwrap();
+
+            let expected =
+                utils::test::get_test_data_file_contents("tera/renders/gitlab_markdown/default.md");
+
+            pretty_assertions::assert_eq!(expected, result);
+        }
+
+        #[test]
+        fn addresses() {
+            let data = tf::tests::get_test_data();
+            let result = render_github_parts(&data, &GithubOptions::gitlab(), 600).unwrap();
+
+            assert!(result[0].starts_with("<!-- part 1/3 -->\n- :white_check_mark: "));
+        }
+    }
+
+    mod render_github_parts {
+        use super::*;
+        use crate::utils;
+
+        #[test]
+        fn fits() {
+            let data = tf::tests::get_test_data();
+            let options = GithubOptions::default();
+            let result = render_github_parts(&data, &options, split::GITHUB_COMMENT_LIMIT).unwrap();
+
+            assert_eq!(result, vec![render_github(&data, &options).unwrap()]);
+        }
+
+        #[test]
+        fn without_unchanged_values() {
+            let data = tf::tests::get_test_data();
+            let options = GithubOptions {
+                show_changed_values: true,
+                ..GithubOptions::default()
+            };
+            let reduced = render_github(&data, &GithubOptions::default()).unwrap();
+            let max_size = reduced.chars().count();
+            let result = render_github_parts(&data, &options, max_size).unwrap();
+
+            assert_eq!(result, vec![reduced]);
+        }
+
+        #[test]
+        fn addresses() {
+            let data = tf::tests::get_test_data();
+            let result = render_github_parts(&data, &GithubOptions::default(), 1000).unwrap();
+
+            let expected = utils::test::get_test_data_file_contents(
+                "tera/renders/github_markdown/addresses.md",
+            );
+            assert_eq!(result, vec![expected]);
+        }
+
+        #[test]
+        fn summary() {
+            let data = tf::tests::get_test_data();
+            let options = GithubOptions {
+                summary: true,
+                ..GithubOptions::default()
+            };
+            let result = render_github_parts(&data, &options, 1000).unwrap();
+
+            assert!(result.len() > 1);
+            let summary = render_github(&data, &options).unwrap();
+            let summary = &summary[..summary.find("<details>").unwrap()];
+            assert!(result[0].contains(summary.trim()));
+        }
+
+        #[test]
+        fn split() {
+            let data = tf::tests::get_test_data();
+            let result = render_github_parts(&data, &GithubOptions::default(), 500).unwrap();
+
+            assert!(result.len() > 1);
+            assert!(result.iter().all(|part| part.chars().count() <= 500));
+            assert!(result[0].starts_with(&format!("<!-- part 1/{} -->", result.len())));
+            let summary_lines = data.plans.len();
+            assert_eq!(
+                result[0]
+                    .lines()
+                    .filter(|line| line.starts_with("- ") && line.contains("terraform.tfplan.json"))
+                    .count(),
+                summary_lines
+            );
+        }
     }
 }
@@ -0,0 +1,189 @@
+use crate::types;
+
+/// GitHub rejects issue and pull request comments above this size
+pub const GITHUB_COMMENT_LIMIT: usize = 65536;
+/// GitLab rejects notes above this size
+pub const GITLAB_NOTE_LIMIT: usize = 1_000_000;
+
+/// Lines starting with the prefix open a section, sections are continued in the next part
+pub const SECTION_PREFIX: &str = "### ";
+const CONTINUED_SUFFIX: &str = " (continued)";
+
+fn part_marker(index: usize, count: usize) -> String {
+    format!("<!-- part {index}/{count} -->")
+}
+
+/// Checks whether the text fits into the size, sizes are measured in characters
+#[must_use]
+pub fn fits(text: &str, max_size: usize) -> bool {
+    text.chars().count() <= max_size
+}
+
+/// Splits text into numbered parts of at most `max_size` characters.
+/// Lines before the first section form the summary and always open the first part,
+/// sections split across parts repeat their header in the next part.
+///
+/// # Errors
+/// Returns an error if the text has to be split and the summary doesn't fit into the first part
+/// or a line doesn't fit into a part of its own
+pub fn split(text: &str, max_size: usize) -> Result<Vec<String>, types::Error> {
+    // Reserved for the part marker, allows up to 9999 parts
+    let budget = max_size.saturating_sub(part_marker(9999, 9999).len() + 1);
+
+    let summary_size: usize = text
+        .lines()
+        .take_while(|line| !line.starts_with(SECTION_PREFIX))
+        .map(|line| line.chars().count() + 1)
+        .sum();
+
+    let mut parts: Vec<Vec<String>> = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut current_size = 0;
+    let mut header: Option<&str> = None;
+
+    for line in text.lines() {
+        let is_header = line.starts_with(SECTION_PREFIX);
+        if is_header {
+            header = Some(line);
+        }
+        let line_size = line.chars().count() + 1;
+        if header.is_some() && !current.is_empty() && current_size + line_size > budget {
+            while current.last().is_some_and(String::is_empty) {
+                current.pop();
+            }
+            // A header without any lines is moved into the next part
+            let dangling =
+                current.len() > 1 && !is_header && current.last().map(String::as_str) == header;
+            if dangling {
+                current.pop();
+            }
+            parts.push(std::mem::take(&mut current));
+            current_size = 0;
+            if let (false, Some(header)) = (is_header, header) {
+                let continued = if dangling {
+                    header.to_string()
+                } else {
+                    format!("{header}{CONTINUED_SUFFIX}")
+                };
+                current_size += continued.chars().count() + 1;
+                current.push(continued);
+            }
+        }
+        current.push(line.to_string());
+        current_size += line_size;
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+
+    if parts.len() <= 1 {
+        return Ok(vec![text.to_string()]);
+    }
+    if summary_size > budget {
+        return Err(types::Error::default(format!(
+            "Summary of {summary_size} characters does not fit into parts of {max_size} characters"
+        )));
+    }
+    for lines in &parts {
+        let size: usize = lines.iter().map(|line| line.chars().count() + 1).sum();
+        if size > budget {
+            let line_size = lines
+                .iter()
+                .map(|line| line.chars().count())
+                .max()
+                .unwrap_or(0);
+            return Err(types::Error::default(format!(
+                "Line of {line_size} characters does not fit into parts of {max_size} characters"
+            )));
+        }
+    }
+    let count = parts.len();
+    Ok(parts
+        .into_iter()
+        .enumerate()
+        .map(|(index, lines)| format!("{}\n{}", part_marker(index + 1, count), lines.join("\n")))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fits_size() {
+        assert!(fits("ab", 2));
+        assert!(fits("✅✅", 2));
+        assert!(!fits("abc", 2));
+    }
+
+    #[test]
+    fn not_split() {
+        let text = "summary\n### section\n- a\n- b";
+        assert_eq!(split(text, 1000).unwrap(), vec![text.to_string()]);
+    }
+
+    #[test]
+    fn sections() {
+        let text = "summary\n### first\n- aaaaaaaaaa\n- bbbbbbbbbb\n### second\n- cccccccccc";
+        let result = split(text, 60).unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                "<!-- part 1/3 -->\nsummary\n### first\n- aaaaaaaaaa".to_string(),
+                "<!-- part 2/3 -->\n### first (continued)\n- bbbbbbbbbb".to_string(),
+                "<!-- part 3/3 -->\n### second\n- cccccccccc".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn summary_not_split() {
+        let text = "summary 1\nsummary 2\nsummary 3\n### first\n- a";
+        let result = split(text, 60).unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                "<!-- part 1/2 -->\nsummary 1\nsummary 2\nsummary 3".to_string(),
+                "<!-- part 2/2 -->\n### first\n- a".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn summary_too_large() {
+        let text = "summary line 1\nsummary line 2\nsummary line 3\n### first\n- a";
+        let result = split(text, 60);
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Summary of 45 characters does not fit into parts of 60 characters"
+        );
+    }
+
+    #[test]
+    fn dangling_header() {
+        let text = "summary\n### first\n- aaaaaaaaaa\n\n### second\n- bbbbbbbbbb";
+        let result = split(text, 60).unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                "<!-- part 1/2 -->\nsummary\n### first\n- aaaaaaaaaa".to_string(),
+                "<!-- part 2/2 -->\n### second\n- bbbbbbbbbb".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn long_line() {
+        let text = format!("### first\n- {}\n- a", "x".repeat(100));
+        let result = split(&text, 60);
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Line of 102 characters does not fit into parts of 60 characters"
+        );
+    }
+}
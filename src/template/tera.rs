@@ -2,65 +2,449 @@ use itertools::Itertools;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::schema;
 use crate::tf;
 use crate::types;
 use core::str;
 use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Arc;
 
 const INDENT_STR: &str = "  ";
 
-pub const GITHUB_MARKDOWN_TEMPLATE: &str = "
-{%- for plan_key, plan in data.plans %}<details>
-<summary>{{ render_actions(actions=plan.unique_actions) }}{{ plan_key }}</summary>
-{%- if not plan.changes %}
-No resource changes
-{%- else %}
-{%- for change in plan.changes %}
+/// Macros shared by the markdown templates, can be imported in custom templates
+/// with `{% import 'markdown' as markdown %}`
+pub const MARKDOWN_MACROS: &str = "
+{%- macro render_diff(change, options) %}```{% if options.style is defined and options.style == 'terraform' %}diff
+{{ render_terraform(change=change, show_changed_values=options.show_changed_values, normalize_empty=options.normalize_empty | default(value=false), max_string_length=options.max_string_length | default(value=0), max_lines=options.max_lines | default(value=0), max_depth=options.max_depth | default(value=0), max_elements=options.max_elements | default(value=0)) }}{% else %}
+{{ render_values(before=change.before, after=change.after, show_changed_values=options.show_changed_values, ignored_paths=change.ignored_paths, normalize_empty=options.normalize_empty | default(value=false), mode=change.mode, type=change.type, diff=change.diff, max_string_length=options.max_string_length | default(value=0), max_lines=options.max_lines | default(value=0), max_depth=options.max_depth | default(value=0), max_elements=options.max_elements | default(value=0)) }}{% endif %}
+```{% endmacro render_diff %}
+{%- macro render_change(change, options) %}
+<details>
+<summary>{{ render_action(action=change.action, symbols=options.symbols | default(value='emoji'), markdown=true) }}{{ change.address }}
+</summary>
+
+{{ self::render_diff(change=change, options=options) }}
+
+</details>
+{%- endmacro render_change %}
+{%- macro render_group(group, changes, options) %}
+{%- set first = group.changes | first %}
+{%- if group.changes | length == 1 %}{{ self::render_change(change=changes[first], options=options) }}
+{%- elif group.identical %}
 <details>
-<summary>{{ render_action(action=change.action) }}{{ change.address }}
+<summary>{{ render_actions(actions=group.unique_actions, symbols=options.symbols | default(value='emoji'), markdown=true) }}{{ group.address }} ({{ group.changes | length }} instances)
 </summary>
 
-```
-{{ render_values(before=change.before, after=change.after, show_changed_values=options.show_changed_values) }}
-```
+Instances: {% for key in group.keys %}`{{ key }}`{% if not loop.last %}, {% endif %}{% endfor %}
 
+{{ self::render_diff(change=changes[first], options=options) }}
+
+</details>
+{%- else %}
+<details>
+<summary>{{ render_actions(actions=group.unique_actions, symbols=options.symbols | default(value='emoji'), markdown=true) }}{{ group.address }} ({{ group.changes | length }} instances)</summary>
+{%- for index in group.changes %}{{ self::render_change(change=changes[index], options=options) }}
+{%- endfor %}
 </details>
+{%- endif %}
+{%- endmacro render_group %}
+{%- macro render_module(module, changes, groups, options) %}
+{%- if groups %}
+{%- for index in module.groups %}{{ self::render_group(group=groups[index], changes=changes, options=options) }}
+{%- endfor %}
+{%- else %}
+{%- for index in module.changes %}{{ self::render_change(change=changes[index], options=options) }}
 {%- endfor %}
 {%- endif %}
+{%- for child in module.modules %}
+<details>
+<summary>{{ render_actions(actions=child.unique_actions, symbols=options.symbols | default(value='emoji'), markdown=true) }}{{ child.address }}</summary>
+{{- self::render_module(module=child, changes=changes, groups=groups, options=options) }}
 </details>
-{% endfor %}";
+{%- endfor %}
+{%- endmacro render_module %}
+{%- macro render_summary(data, options) %}{%- if options.summary is defined and options.summary %}
+| Plan | {{ render_action(action='Create', symbols=options.symbols | default(value='emoji'), markdown=true) | trim }} Add | {{ render_action(action='Update', symbols=options.symbols | default(value='emoji'), markdown=true) | trim }} Change | {{ render_action(action='DeleteCreate', symbols=options.symbols | default(value='emoji'), markdown=true) | trim }} Replace | {{ render_action(action='Delete', symbols=options.symbols | default(value='emoji'), markdown=true) | trim }} Destroy | {{ render_action(action='Read', symbols=options.symbols | default(value='emoji'), markdown=true) | trim }} Read | {{ render_action(action='NoOp', symbols=options.symbols | default(value='emoji'), markdown=true) | trim }} No-op |
+| --- | ---: | ---: | ---: | ---: | ---: | ---: |
+{%- for plan_key, plan in data.plans %}
+| {{ plan_key }} | {{ plan.counts.create }} | {{ plan.counts.update }} | {{ plan.counts.delete_create }} | {{ plan.counts.delete }} | {{ plan.counts.read }} | {{ plan.counts.no_op }} |
+{%- endfor %}
+| **Total** | **{{ data.counts.create }}** | **{{ data.counts.update }}** | **{{ data.counts.delete_create }}** | **{{ data.counts.delete }}** | **{{ data.counts.read }}** | **{{ data.counts.no_op }}** |
+
+Plan: {{ data.counts.add }} to add, {{ data.counts.change }} to change, {{ data.counts.destroy }} to destroy.
+
+{% endif %}{% endmacro render_summary %}
+{%- macro render_plans(plans, options) %}
+{%- for plan_key, plan in plans %}<details>
+<summary>{{ render_actions(actions=plan.unique_actions, symbols=options.symbols | default(value='emoji'), markdown=true) }}{{ plan_key }}</summary>
+{%- if not plan.changes %}
+No resource changes
+{%- else %}
+{%- if plan.modules %}{{ self::render_module(module=plan.modules, changes=plan.changes, groups=plan.groups, options=options) }}
+{%- elif plan.groups %}
+{%- for group in plan.groups %}{{ self::render_group(group=group, changes=plan.changes, options=options) }}
+{%- endfor %}
+{%- else %}
+{%- for change in plan.changes | filter(attribute='cosmetic', value=false) %}{{ self::render_change(change=change, options=options) }}
+{%- endfor %}
+{%- endif %}
+{%- set cosmetic_changes = plan.changes | filter(attribute='cosmetic', value=true) %}
+{%- if cosmetic_changes %}
+<details>
+<summary>Cosmetic changes</summary>
+{% for change in cosmetic_changes %}
+- {{ render_action(action=change.action, symbols=options.symbols | default(value='emoji'), markdown=true) }}{{ change.address }}
+{%- endfor %}
+
+</details>
+{%- endif %}
+{%- endif %}
+</details>
+{% endfor %}{% endmacro render_plans %}";
+/// Github markdown, also used for GitLab with emoji shortcodes
+pub const GITHUB_MARKDOWN_TEMPLATE: &str = "{% import 'markdown' as markdown %}
+{{- markdown::render_summary(data=data, options=options) }}
+{{- markdown::render_plans(plans=data.plans, options=options) }}";
+/// Compact variant of the Github markdown used when the full output exceeds the size limit,
+/// sections start with `### ` so the output can be split into parts.
+/// Changes are listed by their full address, modules and instances are not grouped.
+pub const GITHUB_ADDRESSES_TEMPLATE: &str = "{% import 'markdown' as markdown %}
+{%- if options.summary is defined and options.summary %}{{ markdown::render_summary(data=data, options=options) | trim }}
+{% else %}{% for plan_key, plan in data.plans -%}
+- {{ render_actions(actions=plan.unique_actions, symbols=options.symbols | default(value='emoji'), markdown=true) }}{{ plan_key }}: {{ plan.changes | length }} change{{ plan.changes | length | pluralize }}
+{% endfor %}{% endif %}
+{%- for plan_key, plan in data.plans %}{% if plan.changes %}
+### {{ render_actions(actions=plan.unique_actions, symbols=options.symbols | default(value='emoji'), markdown=true) }}{{ plan_key }}
+{% for change in plan.changes -%}
+- {{ render_action(action=change.action, symbols=options.symbols | default(value='emoji'), markdown=true) }}{{ change.address }}{% if change.cosmetic %} (cosmetic){% endif %}
+{% endfor %}
+{%- endif %}{% endfor %}";
 const DEFAULT_SHOW_CHANGED_VALUES: bool = true;
 
 type Args = std::collections::HashMap<String, tera::Value>;
 
-fn render_action(action: &tf::Action) -> String {
-    match action {
-        tf::Action::Create => "✅".to_string(),
-        tf::Action::Delete => "❌".to_string(),
-        tf::Action::DeleteCreate => "♻️".to_string(),
-        tf::Action::Update => "🔄".to_string(),
-        tf::Action::NoOp => "🟰".to_string(),
-        tf::Action::Read => "🔍".to_string(),
-        tf::Action::Unknown => "❓".to_string(),
+struct ValuesOptions<'a> {
+    show_changed_values: bool,
+    ignored_paths: Vec<Vec<String>>,
+    normalize_empty: bool,
+    /// Resource schema, looked up in the provider schemas by `mode` and `type`
+    schema: Option<&'a schema::SchemaNode>,
+    limits: ValuesLimits,
+    /// Indent of top-level attributes, used to compute the nesting depth
+    indent_base: usize,
+    /// Attribute diff of the change, marks values known after apply and forcing replacement
+    diff: Vec<tf::DiffNode>,
+}
+
+/// Limits for huge values, values beyond a limit are replaced with `… (N more)` markers
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValuesLimits {
+    pub max_string_length: Option<usize>,
+    /// Lines of a single added, removed or unchanged value
+    pub max_lines: Option<usize>,
+    pub max_depth: Option<usize>,
+    /// Unchanged array elements, changed elements are always rendered
+    pub max_elements: Option<usize>,
+}
+
+/// Reads an optional limit, zero disables the limit
+fn limit_from_args(args: &Args, key: &str) -> tera::Result<Option<usize>> {
+    match args.get(key) {
+        Some(value) => {
+            Ok(tera::from_value::<Option<usize>>(value.clone())?.filter(|limit| *limit > 0))
+        }
+        None => Ok(None),
     }
 }
 
-fn tera_render_action(args: &Args) -> tera::Result<tera::Value> {
-    let action = args.get("action").ok_or("action must be present in args")?;
-    let action = tera::from_value::<tf::Action>(action.clone())?;
+impl<'a> ValuesOptions<'a> {
+    fn from_args(args: &Args, schemas: &'a schema::Registry) -> tera::Result<Self> {
+        let show_changed_values = match args.get("show_changed_values") {
+            Some(value) => tera::from_value::<bool>(value.clone())?,
+            None => DEFAULT_SHOW_CHANGED_VALUES,
+        };
+        let ignored_paths = match args.get("ignored_paths") {
+            Some(value) => tera::from_value::<Vec<Vec<String>>>(value.clone())?,
+            None => Vec::new(),
+        };
+        let normalize_empty = match args.get("normalize_empty") {
+            Some(value) => tera::from_value::<bool>(value.clone())?,
+            None => false,
+        };
+        let schema = match (args.get("mode"), args.get("type")) {
+            (Some(mode), Some(type_)) => schemas.get(
+                &tera::from_value::<String>(mode.clone())?,
+                &tera::from_value::<String>(type_.clone())?,
+            ),
+            _ => None,
+        };
+        let diff = match args.get("diff") {
+            Some(value) => tera::from_value::<Vec<tf::DiffNode>>(value.clone())?,
+            None => Vec::new(),
+        };
+
+        Ok(ValuesOptions {
+            show_changed_values,
+            ignored_paths,
+            normalize_empty,
+            schema,
+            limits: ValuesLimits {
+                max_string_length: limit_from_args(args, "max_string_length")?,
+                max_lines: limit_from_args(args, "max_lines")?,
+                max_depth: limit_from_args(args, "max_depth")?,
+                max_elements: limit_from_args(args, "max_elements")?,
+            },
+            indent_base: 0,
+            diff,
+        })
+    }
+
+    fn is_equivalent(&self, path: &[String], before: &tf::Value, after: &tf::Value) -> bool {
+        if before == after || (self.normalize_empty && before.is_empty() && after.is_empty()) {
+            return true;
+        }
+        match (before, after) {
+            (tf::Value::Array(before), tf::Value::Array(after)) if self.is_set(path) => self
+                .diff_arrays(path, before, after)
+                .iter()
+                .all(|op| matches!(op, tf::ArrayDiffOp::Unchanged { .. })),
+            _ => false,
+        }
+    }
+
+    /// Options for values decoded from JSON strings, their keys aren't in the diff or the schema
+    fn decoded(&self) -> ValuesOptions<'a> {
+        ValuesOptions {
+            show_changed_values: self.show_changed_values,
+            ignored_paths: self.ignored_paths.clone(),
+            normalize_empty: self.normalize_empty,
+            schema: None,
+            limits: self.limits.clone(),
+            indent_base: self.indent_base,
+            diff: Vec::new(),
+        }
+    }
+
+    fn is_ignored(&self, path: &[String]) -> bool {
+        self.ignored_paths
+            .iter()
+            .any(|pattern| tf::path_matches(pattern, path))
+    }
 
-    Ok(tera::Value::String(render_action(&action)))
+    /// Checks whether values nested at the indent are collapsed
+    fn exceeds_depth(&self, indent_count: usize) -> bool {
+        self.limits
+            .max_depth
+            .is_some_and(|max| indent_count.saturating_sub(self.indent_base) >= max)
+    }
+
+    fn diff_children(&self, path: &[String]) -> &[tf::DiffNode] {
+        if path.is_empty() {
+            return &self.diff;
+        }
+        self.diff_node(path)
+            .map_or(&[], |node| node.children.as_slice())
+    }
+
+    fn diff_node(&self, path: &[String]) -> Option<&tf::DiffNode> {
+        let (step, parent) = path.split_last()?;
+        self.diff_children(parent)
+            .iter()
+            .find(|node| node.path.last() == Some(step))
+    }
+
+    fn is_unknown(&self, path: &[String]) -> bool {
+        self.diff_node(path).is_some_and(|node| node.unknown)
+    }
+
+    /// Sensitive values are masked in both values, only the diff tells whether they changed
+    fn is_sensitive_changed(&self, path: &[String]) -> bool {
+        self.diff_node(path).is_some_and(|node| {
+            node.sensitive
+                && node.kind == tf::DiffKind::Changed
+                && node.before == Some(tf::Value::Sensitive)
+                && node.after == Some(tf::Value::Sensitive)
+        })
+    }
+
+    fn is_block(&self, path: &[String]) -> bool {
+        self.schema
+            .and_then(|schema| schema.find(path))
+            .is_some_and(schema::SchemaNode::is_block)
+    }
+
+    fn is_set(&self, path: &[String]) -> bool {
+        self.schema
+            .and_then(|schema| schema.find(path))
+            .is_some_and(schema::SchemaNode::is_set)
+    }
+
+    /// Sets are compared regardless of the order of their elements
+    fn diff_arrays(
+        &self,
+        path: &[String],
+        before: &[tf::Value],
+        after: &[tf::Value],
+    ) -> Vec<tf::ArrayDiffOp> {
+        if self.is_set(path) {
+            tf::diff_sets(before, after)
+        } else {
+            tf::diff_arrays(before, after)
+        }
+    }
+}
+
+fn child_path(path: &[String], key: &str) -> Vec<String> {
+    let mut child = path.to_vec();
+    child.push(key.to_string());
+    child
+}
+
+/// Symbols used to render actions
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ActionSymbols {
+    #[default]
+    Emoji,
+    /// Emoji shortcodes, e.g. `:white_check_mark:`, rendered by GitLab and Slack
+    Shortcodes,
+    /// Signs used by Terraform, e.g. `+` and `-/+`, for log viewers without emoji
+    Ascii,
+    /// Action names, e.g. `[create]`, read out by screen readers
+    Words,
+}
+
+impl FromStr for ActionSymbols {
+    type Err = types::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "emoji" => Ok(ActionSymbols::Emoji),
+            "shortcodes" => Ok(ActionSymbols::Shortcodes),
+            "ascii" => Ok(ActionSymbols::Ascii),
+            "words" => Ok(ActionSymbols::Words),
+            _ => Err(types::Error::default(format!("Invalid symbols: {s}"))),
+        }
+    }
+}
+
+impl ActionSymbols {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            ActionSymbols::Emoji => "emoji",
+            ActionSymbols::Shortcodes => "shortcodes",
+            ActionSymbols::Ascii => "ascii",
+            ActionSymbols::Words => "words",
+        }
+    }
+}
+
+#[must_use]
+pub fn render_action(action: &tf::Action, symbols: &ActionSymbols) -> String {
+    let symbol = match symbols {
+        ActionSymbols::Emoji => match action {
+            tf::Action::Create => "✅",
+            tf::Action::Delete => "❌",
+            tf::Action::DeleteCreate => "♻️",
+            tf::Action::Update => "🔄",
+            tf::Action::NoOp => "🟰",
+            tf::Action::Read => "🔍",
+            tf::Action::Unknown => "❓",
+        },
+        ActionSymbols::Shortcodes => match action {
+            tf::Action::Create => ":white_check_mark:",
+            tf::Action::Delete => ":x:",
+            tf::Action::DeleteCreate => ":recycle:",
+            tf::Action::Update => ":arrows_counterclockwise:",
+            tf::Action::NoOp => ":white_circle:",
+            tf::Action::Read => ":mag:",
+            tf::Action::Unknown => ":question:",
+        },
+        ActionSymbols::Ascii => match action {
+            tf::Action::Create => "+",
+            tf::Action::Delete => "-",
+            tf::Action::DeleteCreate => "-/+",
+            tf::Action::Update => "~",
+            tf::Action::NoOp => "=",
+            tf::Action::Read => "<=",
+            tf::Action::Unknown => "?",
+        },
+        ActionSymbols::Words => return format!("[{}] ", action.as_str()),
+    };
+    match symbols {
+        ActionSymbols::Emoji => symbol.to_string(),
+        // Shortcodes are only rendered when separated from the surrounding text,
+        // signs are separated to stay readable
+        ActionSymbols::Shortcodes | ActionSymbols::Ascii | ActionSymbols::Words => {
+            format!("{symbol} ")
+        }
+    }
+}
+
+/// Renders the action for markdown, signs and words are wrapped in code spans
+/// as e.g. `-` would start a list item and `~` strike text through
+#[must_use]
+pub fn render_markdown_action(action: &tf::Action, symbols: &ActionSymbols) -> String {
+    match symbols {
+        ActionSymbols::Emoji | ActionSymbols::Shortcodes => render_action(action, symbols),
+        ActionSymbols::Ascii | ActionSymbols::Words => {
+            format!("`{}` ", render_action(action, symbols).trim_end())
+        }
+    }
 }
 
-fn tera_render_actions(args: &Args) -> tera::Result<tera::Value> {
-    let actions = args
-        .get("actions")
-        .ok_or("actions must be present in args")?;
-    let actions = tera::from_value::<Vec<tf::Action>>(actions.clone())?;
+fn render_action_from_args(args: &Args) -> tera::Result<fn(&tf::Action, &ActionSymbols) -> String> {
+    let markdown = match args.get("markdown") {
+        Some(value) => tera::from_value::<bool>(value.clone())?,
+        None => false,
+    };
+    Ok(if markdown {
+        render_markdown_action
+    } else {
+        render_action
+    })
+}
+
+fn symbols_from_args(args: &Args, default: &ActionSymbols) -> tera::Result<ActionSymbols> {
+    match args.get("symbols") {
+        Some(symbols) => {
+            let symbols = tera::from_value::<String>(symbols.clone())?;
+            ActionSymbols::from_str(&symbols).map_err(|e| tera::Error::msg(e.to_string()))
+        }
+        None => Ok(default.clone()),
+    }
+}
 
-    let result: Vec<String> = actions.iter().map(render_action).collect();
+/// Symbols not given in args default to the symbols of the render
+fn tera_render_action(symbols: ActionSymbols) -> impl tera::Function {
+    move |args: &Args| -> tera::Result<tera::Value> {
+        let action = args.get("action").ok_or("action must be present in args")?;
+        let action = tera::from_value::<tf::Action>(action.clone())?;
+        let symbols = symbols_from_args(args, &symbols)?;
+        let render = render_action_from_args(args)?;
 
-    Ok(tera::Value::String(result.join("")))
+        Ok(tera::Value::String(render(&action, &symbols)))
+    }
+}
+
+/// Symbols not given in args default to the symbols of the render
+fn tera_render_actions(symbols: ActionSymbols) -> impl tera::Function {
+    move |args: &Args| -> tera::Result<tera::Value> {
+        let actions = args
+            .get("actions")
+            .ok_or("actions must be present in args")?;
+        let actions = tera::from_value::<Vec<tf::Action>>(actions.clone())?;
+        let symbols = symbols_from_args(args, &symbols)?;
+        let render = render_action_from_args(args)?;
+
+        let result: Vec<String> = actions
+            .iter()
+            .map(|action| render(action, &symbols))
+            .collect();
+
+        Ok(tera::Value::String(result.join("")))
+    }
 }
 
 fn render_plaintext(value: &tf::Value) -> String {
@@ -69,6 +453,7 @@ fn render_plaintext(value: &tf::Value) -> String {
         tf::Value::String(value) => format!("{}", tera::to_value(value).unwrap()),
         tf::Value::Integer(value) => format!("{}", tera::to_value(value).unwrap()),
         tf::Value::Float(value) => format!("{}", tera::to_value(value).unwrap()),
+        tf::Value::Number(value) => value.clone(),
         tf::Value::Boolean(value) => format!("{}", tera::to_value(value).unwrap()),
         tf::Value::Array(value) => format!(
             "[{}]",
@@ -78,26 +463,133 @@ fn render_plaintext(value: &tf::Value) -> String {
                 .collect::<Vec<String>>()
                 .join(", ")
         ),
-        tf::Value::Object(value) => format!("{}", tera::to_value(value).unwrap()),
+        tf::Value::Object(value) => format!(
+            "{{{}}}",
+            value
+                .iter()
+                .sorted_by_key(|x| x.0)
+                .map(|(key, value)| format!("{key}: {}", render_plaintext(value)))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
         tf::Value::Null => "null".to_string(),
     }
 }
 
-fn render_unchanged_plaintext(key: &str, value: &tf::Value, indent_count: usize) -> Vec<String> {
+fn tera_render_value(args: &Args) -> tera::Result<tera::Value> {
+    let value = args.get("value").ok_or("value must be present in args")?;
+    let value = tera::from_value::<Option<tf::Value>>(value.clone())?;
+
+    Ok(tera::Value::String(
+        value.as_ref().map(render_plaintext).unwrap_or_default(),
+    ))
+}
+
+fn is_nested(value: &tf::Value) -> bool {
+    match value {
+        tf::Value::Object(_) => true,
+        tf::Value::Array(values) => values.iter().any(is_nested),
+        _ => false,
+    }
+}
+
+fn more_marker(count: usize, noun: &str) -> String {
+    let plural = if count == 1 { "" } else { "s" };
+    format!("… ({count} more {noun}{plural})")
+}
+
+/// Renders nested values beyond the depth limit as a single line
+fn render_collapsed(value: &tf::Value) -> String {
+    match value {
+        tf::Value::Object(map) if !map.is_empty() => {
+            format!("{{{}}}", more_marker(map.len(), "key"))
+        }
+        tf::Value::Array(values) if !values.is_empty() => {
+            format!("[{}]", more_marker(values.len(), "element"))
+        }
+        _ => render_plaintext(value),
+    }
+}
+
+fn render_limited_plaintext(value: &tf::Value, options: &ValuesOptions) -> String {
+    match value {
+        tf::Value::String(value) => match options.limits.max_string_length {
+            Some(max) if value.chars().count() > max => {
+                let truncated: String = value.chars().take(max).collect();
+                format!(
+                    "{}{}",
+                    tera::to_value(truncated).unwrap(),
+                    more_marker(value.chars().count() - max, "character")
+                )
+            }
+            _ => render_plaintext(&tf::Value::String(value.clone())),
+        },
+        tf::Value::Array(values) => {
+            let max = options.limits.max_elements.unwrap_or(values.len());
+            let mut elements: Vec<String> = values
+                .iter()
+                .take(max)
+                .map(|value| render_limited_plaintext(value, options))
+                .collect();
+            if values.len() > max {
+                elements.push(more_marker(values.len() - max, "element"));
+            }
+            format!("[{}]", elements.join(", "))
+        }
+        tf::Value::Object(map) => format!(
+            "{{{}}}",
+            map.iter()
+                .sorted_by_key(|x| x.0)
+                .map(|(key, value)| format!("{key}: {}", render_limited_plaintext(value, options)))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        _ => render_plaintext(value),
+    }
+}
+
+/// Truncates the lines of a single value, the first line always stays to keep the key visible
+fn limit_lines(
+    mut lines: Vec<String>,
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    match options.limits.max_lines {
+        Some(max) if lines.len() > max.max(1) => {
+            let count = lines.len() - max.max(1);
+            lines.truncate(max.max(1));
+            lines.push(format!(
+                "{}{}",
+                INDENT_STR.repeat(indent_count + 1),
+                more_marker(count, "line")
+            ));
+            lines
+        }
+        _ => lines,
+    }
+}
+
+fn render_unchanged_plaintext(
+    key: &str,
+    value: &tf::Value,
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
     vec![format!(
         "{}{key}: {}",
         INDENT_STR.repeat(indent_count),
-        render_plaintext(value)
+        render_limited_plaintext(value, options)
     )]
 }
 
 fn render_unchanged_hashmap_value(
     value: &std::collections::HashMap<String, tf::Value>,
     indent_count: usize,
+    options: &ValuesOptions,
 ) -> Vec<String> {
     let mut result: Vec<String> = Vec::new();
     for (key, value) in value.iter().sorted_by_key(|x| x.0) {
-        result.extend(render_unchanged(key, value, indent_count));
+        result.extend(render_unchanged(key, value, indent_count, options));
     }
     result
 }
@@ -106,18 +598,95 @@ fn render_unchanged_hashmap(
     key: &str,
     value: &std::collections::HashMap<String, tf::Value>,
     indent_count: usize,
+    options: &ValuesOptions,
 ) -> Vec<String> {
     let mut result: Vec<String> = Vec::new();
     result.push(format!("{}{key}:", INDENT_STR.repeat(indent_count)));
-    result.extend(render_unchanged_hashmap_value(value, indent_count + 1));
+    result.extend(render_unchanged_hashmap_value(
+        value,
+        indent_count + 1,
+        options,
+    ));
     result
 }
 
-fn render_unchanged(key: &str, value: &tf::Value, indent_count: usize) -> Vec<String> {
+fn render_unchanged_array(
+    key: &str,
+    values: &[tf::Value],
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    result.push(format!("{}{key}:", INDENT_STR.repeat(indent_count)));
+    let max = options.limits.max_elements.unwrap_or(values.len());
+    for (index, value) in values.iter().enumerate().take(max) {
+        result.extend(render_unchanged(
+            &format!("[{index}]"),
+            value,
+            indent_count + 1,
+            options,
+        ));
+    }
+    if values.len() > max {
+        result.push(format!(
+            "{}{}",
+            INDENT_STR.repeat(indent_count + 1),
+            more_marker(values.len() - max, "element")
+        ));
+    }
+    result
+}
+
+fn render_unchanged(
+    key: &str,
+    value: &tf::Value,
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
     match value {
-        tf::Value::Object(map) => render_unchanged_hashmap(key, map, indent_count),
-        _ => render_unchanged_plaintext(key, value, indent_count),
+        tf::Value::Object(map) if options.exceeds_depth(indent_count + 1) && !map.is_empty() => {
+            vec![format!(
+                "{}{key}: {}",
+                INDENT_STR.repeat(indent_count),
+                render_collapsed(value)
+            )]
+        }
+        tf::Value::Array(_) if options.exceeds_depth(indent_count + 1) && is_nested(value) => {
+            vec![format!(
+                "{}{key}: {}",
+                INDENT_STR.repeat(indent_count),
+                render_collapsed(value)
+            )]
+        }
+        tf::Value::Object(map) => render_unchanged_hashmap(key, map, indent_count, options),
+        tf::Value::Array(values) if is_nested(value) => {
+            render_unchanged_array(key, values, indent_count, options)
+        }
+        _ => render_unchanged_plaintext(key, value, indent_count, options),
+    }
+}
+
+/// Renders a whole value, truncated to the line limit
+fn render_unchanged_value(
+    key: &str,
+    value: &tf::Value,
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    limit_lines(
+        render_unchanged(key, value, indent_count, options),
+        indent_count,
+        options,
+    )
+}
+
+/// Renders the attributes of a created or deleted resource, each attribute is limited separately
+fn render_unchanged_values(values: &tf::ValueMap, options: &ValuesOptions) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for (key, value) in values.iter().sorted_by_key(|x| x.0) {
+        result.extend(render_unchanged_value(key, value, 0, options));
     }
+    result
 }
 
 fn render_changed_plaintext(
@@ -125,20 +694,28 @@ fn render_changed_plaintext(
     before_value: &tf::Value,
     after_value: &tf::Value,
     indent_count: usize,
+    options: &ValuesOptions,
 ) -> Vec<String> {
+    let before = render_limited_plaintext(before_value, options);
+    let after = render_limited_plaintext(after_value, options);
+    // Truncated values can look equal, the change is still reported
+    let suffix = if before == after && before_value != after_value {
+        " (changed beyond truncation)"
+    } else {
+        ""
+    };
     vec![format!(
-        "{}{key}: {} -> {}",
+        "{}{key}: {before} -> {after}{suffix}",
         INDENT_STR.repeat(indent_count),
-        render_plaintext(before_value),
-        render_plaintext(after_value)
     )]
 }
 
 fn render_changed_hashmap_value(
     before: &std::collections::HashMap<String, tf::Value>,
     after: &std::collections::HashMap<String, tf::Value>,
+    path: &[String],
     indent_count: usize,
-    show_changed_values: bool,
+    options: &ValuesOptions,
 ) -> Vec<String> {
     let mut keys: HashSet<String> = HashSet::new();
     keys.extend(before.keys().cloned());
@@ -146,14 +723,19 @@ fn render_changed_hashmap_value(
 
     let mut result: Vec<String> = Vec::new();
     for key in keys.iter().sorted() {
+        let key_path = child_path(path, key);
+        if options.is_ignored(&key_path) {
+            continue;
+        }
         let before_value = before.get(key).unwrap_or(&tf::Value::Null);
         let after_value = after.get(key).unwrap_or(&tf::Value::Null);
         result.extend(render_changed(
             key,
             before_value,
             after_value,
+            &key_path,
             indent_count,
-            show_changed_values,
+            options,
         ));
     }
     result
@@ -163,17 +745,104 @@ fn render_changed_hashmap(
     key: &str,
     before: &std::collections::HashMap<String, tf::Value>,
     after: &std::collections::HashMap<String, tf::Value>,
+    path: &[String],
     indent_count: usize,
-    show_changed_values: bool,
+    options: &ValuesOptions,
 ) -> Vec<String> {
+    let children = render_changed_hashmap_value(before, after, path, indent_count + 1, options);
+    // Parents of ignored or hidden unchanged values only are skipped
+    if children.is_empty() {
+        return Vec::new();
+    }
     let mut result: Vec<String> = Vec::new();
     result.push(format!("{}{key}:", INDENT_STR.repeat(indent_count)));
-    result.extend(render_changed_hashmap_value(
-        before,
-        after,
-        indent_count + 1,
-        show_changed_values,
-    ));
+    result.extend(children);
+    result
+}
+
+/// Index used in rendered paths, removed elements keep their original index
+fn array_diff_op_index(op: &tf::ArrayDiffOp) -> usize {
+    match op {
+        tf::ArrayDiffOp::Unchanged { after, .. }
+        | tf::ArrayDiffOp::Changed { after, .. }
+        | tf::ArrayDiffOp::Added { after } => *after,
+        tf::ArrayDiffOp::Removed { before } => *before,
+    }
+}
+
+fn render_changed_array(
+    key: &str,
+    before: &[tf::Value],
+    after: &[tf::Value],
+    path: &[String],
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    result.push(format!("{}{key}:", INDENT_STR.repeat(indent_count)));
+    // Only unchanged elements count towards the element limit
+    let mut unchanged_count = 0;
+    let mut hidden_count = 0;
+    for op in options.diff_arrays(path, before, after) {
+        if options.is_ignored(&child_path(path, &array_diff_op_index(&op).to_string())) {
+            continue;
+        }
+        match op {
+            tf::ArrayDiffOp::Unchanged { after: index, .. } => {
+                if !options.show_changed_values {
+                    continue;
+                }
+                unchanged_count += 1;
+                if options
+                    .limits
+                    .max_elements
+                    .is_some_and(|max| unchanged_count > max)
+                {
+                    hidden_count += 1;
+                    continue;
+                }
+                result.extend(render_unchanged_value(
+                    &format!("[{index}]"),
+                    &after[index],
+                    indent_count + 1,
+                    options,
+                ));
+            }
+            tf::ArrayDiffOp::Changed {
+                before: before_index,
+                after: after_index,
+            } => result.extend(render_changed(
+                &format!("[{after_index}]"),
+                &before[before_index],
+                &after[after_index],
+                &child_path(path, &after_index.to_string()),
+                indent_count + 1,
+                options,
+            )),
+            tf::ArrayDiffOp::Removed { before: index } => result.extend(render_unchanged_value(
+                &format!("(removed, was [{index}])"),
+                &before[index],
+                indent_count + 1,
+                options,
+            )),
+            tf::ArrayDiffOp::Added { after: index } => result.extend(render_unchanged_value(
+                &format!("[{index}] (added)"),
+                &after[index],
+                indent_count + 1,
+                options,
+            )),
+        }
+    }
+    if hidden_count > 0 {
+        result.push(format!(
+            "{}{}",
+            INDENT_STR.repeat(indent_count + 1),
+            more_marker(hidden_count, "element")
+        ));
+    }
+    if result.len() == 1 {
+        return Vec::new();
+    }
     result
 }
 
@@ -181,24 +850,76 @@ fn render_changed(
     key: &str,
     before_value: &tf::Value,
     after_value: &tf::Value,
+    path: &[String],
     indent_count: usize,
-    show_changed_values: bool,
+    options: &ValuesOptions,
 ) -> Vec<String> {
     match (before_value, after_value) {
+        _ if options.is_sensitive_changed(path) => vec![format!(
+            "{}{key}: (sensitive value) (changed)",
+            INDENT_STR.repeat(indent_count)
+        )],
+        _ if before_value != after_value
+            && options.is_equivalent(path, before_value, after_value) =>
+        {
+            if options.show_changed_values {
+                render_unchanged_plaintext(key, before_value, indent_count, options)
+            } else {
+                Vec::new()
+            }
+        }
+        (
+            tf::Value::Object(_) | tf::Value::Array(_),
+            tf::Value::Object(_) | tf::Value::Array(_),
+        ) if before_value != after_value && options.exceeds_depth(indent_count + 1) => {
+            vec![format!(
+                "{}{key}: {} -> {}",
+                INDENT_STR.repeat(indent_count),
+                render_collapsed(before_value),
+                render_collapsed(after_value)
+            )]
+        }
         (tf::Value::Object(before), tf::Value::Object(after)) => {
-            render_changed_hashmap(key, before, after, indent_count, show_changed_values)
+            render_changed_hashmap(key, before, after, path, indent_count, options)
+        }
+        (tf::Value::Null, tf::Value::Object(_)) => {
+            render_unchanged_value(key, after_value, indent_count, options)
+        }
+        (tf::Value::Object(_), tf::Value::Null) => {
+            render_unchanged_value(key, before_value, indent_count, options)
+        }
+        (tf::Value::Array(before), tf::Value::Array(after)) if before != after => {
+            render_changed_array(key, before, after, path, indent_count, options)
         }
-        (tf::Value::Null, tf::Value::Object(after)) => {
-            render_unchanged_hashmap(key, after, indent_count)
+        (tf::Value::Null, tf::Value::Array(_)) if is_nested(after_value) => {
+            render_unchanged_value(key, after_value, indent_count, options)
         }
-        (tf::Value::Object(before), tf::Value::Null) => {
-            render_unchanged_hashmap(key, before, indent_count)
+        (tf::Value::Array(_), tf::Value::Null) if is_nested(before_value) => {
+            render_unchanged_value(key, before_value, indent_count, options)
+        }
+        (tf::Value::String(before), tf::Value::String(after)) if before != after => {
+            match (
+                tf::Value::from_json_string(before),
+                tf::Value::from_json_string(after),
+            ) {
+                (Some(before), Some(after)) if before != after => render_changed(
+                    &format!("{key} (json)"),
+                    &before,
+                    &after,
+                    path,
+                    indent_count,
+                    &options.decoded(),
+                ),
+                _ => {
+                    render_changed_plaintext(key, before_value, after_value, indent_count, options)
+                }
+            }
         }
         (_, _) => {
             if before_value != after_value {
-                render_changed_plaintext(key, before_value, after_value, indent_count)
-            } else if show_changed_values {
-                render_unchanged_plaintext(key, before_value, indent_count)
+                render_changed_plaintext(key, before_value, after_value, indent_count, options)
+            } else if options.show_changed_values {
+                render_unchanged_plaintext(key, before_value, indent_count, options)
             } else {
                 Vec::new()
             }
@@ -206,38 +927,917 @@ fn render_changed(
     }
 }
 
-fn tera_render_values(args: &Args) -> tera::Result<tera::Value> {
+fn terraform_line(sign: &str, indent_count: usize, content: &str) -> String {
+    format!("{sign} {}{content}", INDENT_STR.repeat(indent_count))
+}
+
+fn terraform_key_width<'a>(keys: impl Iterator<Item = &'a String>) -> usize {
+    keys.map(|key| key.chars().count()).max().unwrap_or(0)
+}
+
+fn terraform_hidden_line(count: usize, noun: &str, indent_count: usize) -> String {
+    let plural = if count == 1 { "" } else { "s" };
+    terraform_line(
+        " ",
+        indent_count,
+        &format!("# ({count} unchanged {noun}{plural} hidden)"),
+    )
+}
+
+fn render_terraform_plaintext(value: &tf::Value, options: &ValuesOptions) -> String {
+    match value {
+        tf::Value::Sensitive => "(sensitive value)".to_string(),
+        _ => render_limited_plaintext(value, options),
+    }
+}
+
+fn render_terraform_value(
+    sign: &str,
+    prefix: &str,
+    value: &tf::Value,
+    suffix: &str,
+    path: &[String],
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    match value {
+        tf::Value::Object(_) | tf::Value::Array(_)
+            if options.exceeds_depth(indent_count + 1) && !value.is_empty() =>
+        {
+            vec![terraform_line(
+                sign,
+                indent_count,
+                &format!("{prefix}{}{suffix}", render_collapsed(value)),
+            )]
+        }
+        tf::Value::Object(map) if !map.is_empty() => {
+            let mut result = vec![terraform_line(sign, indent_count, &format!("{prefix}{{"))];
+            let (blocks, attributes): (Vec<_>, Vec<_>) = map
+                .iter()
+                .filter(|(_, value)| **value != tf::Value::Null)
+                .sorted_by_key(|x| x.0)
+                .partition(|(key, _)| options.is_block(&child_path(path, key)));
+            let width = terraform_key_width(attributes.iter().map(|x| x.0));
+            for (key, value) in attributes {
+                result.extend(render_terraform_value(
+                    sign,
+                    &format!("{key:width$} = "),
+                    value,
+                    "",
+                    &child_path(path, key),
+                    indent_count + 1,
+                    options,
+                ));
+            }
+            for (key, value) in blocks {
+                result.extend(render_terraform_block_value(
+                    sign,
+                    key,
+                    value,
+                    &child_path(path, key),
+                    indent_count + 1,
+                    options,
+                ));
+            }
+            result.push(terraform_line(sign, indent_count, &format!("}}{suffix}")));
+            result
+        }
+        tf::Value::Array(values) if !values.is_empty() => {
+            let mut result = vec![terraform_line(sign, indent_count, &format!("{prefix}["))];
+            let max = options.limits.max_elements.unwrap_or(values.len());
+            for (index, value) in values.iter().enumerate().take(max) {
+                result.extend(render_terraform_value(
+                    sign,
+                    "",
+                    value,
+                    ",",
+                    &child_path(path, &index.to_string()),
+                    indent_count + 1,
+                    options,
+                ));
+            }
+            if values.len() > max {
+                result.push(terraform_line(
+                    sign,
+                    indent_count + 1,
+                    &more_marker(values.len() - max, "element"),
+                ));
+            }
+            result.push(terraform_line(sign, indent_count, &format!("]{suffix}")));
+            result
+        }
+        _ => vec![terraform_line(
+            sign,
+            indent_count,
+            &format!(
+                "{prefix}{}{suffix}",
+                render_terraform_plaintext(value, options)
+            ),
+        )],
+    }
+}
+
+/// Renders a whole value, truncated to the line limit while keeping the closing line
+fn render_terraform_limited_value(
+    sign: &str,
+    prefix: &str,
+    value: &tf::Value,
+    suffix: &str,
+    path: &[String],
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    let mut lines =
+        render_terraform_value(sign, prefix, value, suffix, path, indent_count, options);
+    match options.limits.max_lines {
+        Some(max) if lines.len() > max.max(1) + 1 => {
+            let last = lines.pop().unwrap_or_default();
+            let count = lines.len() - max.max(1);
+            lines.truncate(max.max(1));
+            lines.push(terraform_line(
+                sign,
+                indent_count + 1,
+                &more_marker(count, "line"),
+            ));
+            lines.push(last);
+            lines
+        }
+        _ => lines,
+    }
+}
+
+/// Nested blocks are rendered as `key {`, lists and sets of blocks repeat the block per element
+fn render_terraform_block_value(
+    sign: &str,
+    key: &str,
+    value: &tf::Value,
+    path: &[String],
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    match value {
+        tf::Value::Array(values) => values
+            .iter()
+            .enumerate()
+            .flat_map(|(index, value)| {
+                render_terraform_value(
+                    sign,
+                    &format!("{key} "),
+                    value,
+                    "",
+                    &child_path(path, &index.to_string()),
+                    indent_count,
+                    options,
+                )
+            })
+            .collect(),
+        _ => render_terraform_value(
+            sign,
+            &format!("{key} "),
+            value,
+            "",
+            path,
+            indent_count,
+            options,
+        ),
+    }
+}
+
+/// Returns the rendered lines and the count of hidden unchanged blocks
+fn render_terraform_changed_block_list(
+    prefix: &str,
+    before: &[tf::Value],
+    after: &[tf::Value],
+    path: &[String],
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> (Vec<String>, usize) {
+    let mut result: Vec<String> = Vec::new();
+    let mut shown_count = 0;
+    let mut hidden_count = 0;
+    for op in options.diff_arrays(path, before, after) {
+        if options.is_ignored(&child_path(path, &array_diff_op_index(&op).to_string())) {
+            continue;
+        }
+        match op {
+            tf::ArrayDiffOp::Unchanged { after: index, .. } => {
+                shown_count += 1;
+                let exceeds_limit = options
+                    .limits
+                    .max_elements
+                    .is_some_and(|max| shown_count > max);
+                if options.show_changed_values && !exceeds_limit {
+                    result.extend(render_terraform_limited_value(
+                        " ",
+                        prefix,
+                        &after[index],
+                        "",
+                        &child_path(path, &index.to_string()),
+                        indent_count,
+                        options,
+                    ));
+                } else {
+                    hidden_count += 1;
+                }
+            }
+            tf::ArrayDiffOp::Changed {
+                before: before_index,
+                after: after_index,
+            } => result.extend(render_terraform_changed(
+                prefix,
+                &before[before_index],
+                &after[after_index],
+                "",
+                &child_path(path, &after_index.to_string()),
+                indent_count,
+                options,
+            )),
+            tf::ArrayDiffOp::Removed { before: index } => {
+                result.extend(render_terraform_limited_value(
+                    "-",
+                    prefix,
+                    &before[index],
+                    "",
+                    &child_path(path, &index.to_string()),
+                    indent_count,
+                    options,
+                ));
+            }
+            tf::ArrayDiffOp::Added { after: index } => {
+                result.extend(render_terraform_limited_value(
+                    "+",
+                    prefix,
+                    &after[index],
+                    "",
+                    &child_path(path, &index.to_string()),
+                    indent_count,
+                    options,
+                ));
+            }
+        }
+    }
+    (result, hidden_count)
+}
+
+/// Returns the rendered lines and the count of hidden unchanged blocks
+fn render_terraform_changed_block(
+    key: &str,
+    before_value: &tf::Value,
+    after_value: &tf::Value,
+    path: &[String],
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> (Vec<String>, usize) {
+    let prefix = format!("{key} ");
+    match (before_value, after_value) {
+        (tf::Value::Array(_) | tf::Value::Null, tf::Value::Array(_) | tf::Value::Null) => {
+            let empty = Vec::new();
+            let before = match before_value {
+                tf::Value::Array(before) => before,
+                _ => &empty,
+            };
+            let after = match after_value {
+                tf::Value::Array(after) => after,
+                _ => &empty,
+            };
+            render_terraform_changed_block_list(&prefix, before, after, path, indent_count, options)
+        }
+        (tf::Value::Null, _) => (
+            render_terraform_limited_value(
+                "+",
+                &prefix,
+                after_value,
+                "",
+                path,
+                indent_count,
+                options,
+            ),
+            0,
+        ),
+        (_, tf::Value::Null) => (
+            render_terraform_limited_value(
+                "-",
+                &prefix,
+                before_value,
+                "",
+                path,
+                indent_count,
+                options,
+            ),
+            0,
+        ),
+        _ => (
+            render_terraform_changed(
+                &prefix,
+                before_value,
+                after_value,
+                "",
+                path,
+                indent_count,
+                options,
+            ),
+            0,
+        ),
+    }
+}
+
+fn render_terraform_changed_hashmap_value(
+    before: &tf::ValueMap,
+    after: &tf::ValueMap,
+    path: &[String],
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    let mut keys: HashSet<&String> = HashSet::new();
+    keys.extend(before.keys());
+    keys.extend(after.keys());
+    keys.extend(
+        options
+            .diff_children(path)
+            .iter()
+            .filter(|node| node.unknown)
+            .filter_map(|node| node.path.last()),
+    );
+
+    let mut changed: Vec<(&String, &tf::Value, &tf::Value)> = Vec::new();
+    let mut blocks: Vec<(&String, &tf::Value, &tf::Value)> = Vec::new();
+    let mut hidden_count = 0;
+    let mut hidden_block_count = 0;
+    for key in keys.into_iter().sorted() {
+        if options.is_ignored(&child_path(path, key)) {
+            continue;
+        }
+        let before_value = before.get(key).unwrap_or(&tf::Value::Null);
+        let after_value = after.get(key).unwrap_or(&tf::Value::Null);
+        // Unknown and changed sensitive values look unchanged in the masked values
+        let is_marked = options.is_unknown(&child_path(path, key))
+            || options.is_sensitive_changed(&child_path(path, key));
+        if before_value == &tf::Value::Null && after_value == &tf::Value::Null && !is_marked {
+            continue;
+        }
+        let is_block = options.is_block(&child_path(path, key)) && !is_marked;
+        if options.is_equivalent(&child_path(path, key), before_value, after_value)
+            && !options.show_changed_values
+            && !is_marked
+        {
+            if is_block {
+                hidden_block_count += 1;
+            } else {
+                hidden_count += 1;
+            }
+            continue;
+        }
+        if is_block {
+            blocks.push((key, before_value, after_value));
+        } else {
+            changed.push((key, before_value, after_value));
+        }
+    }
+
+    let width = terraform_key_width(changed.iter().map(|x| x.0));
+    let mut result: Vec<String> = Vec::new();
+    for (key, before_value, after_value) in changed {
+        result.extend(render_terraform_changed(
+            &format!("{key:width$} = "),
+            before_value,
+            after_value,
+            "",
+            &child_path(path, key),
+            indent_count,
+            options,
+        ));
+    }
+    for (key, before_value, after_value) in blocks {
+        let (lines, count) = render_terraform_changed_block(
+            key,
+            before_value,
+            after_value,
+            &child_path(path, key),
+            indent_count,
+            options,
+        );
+        result.extend(lines);
+        hidden_block_count += count;
+    }
+    if hidden_count > 0 {
+        result.push(terraform_hidden_line(
+            hidden_count,
+            "attribute",
+            indent_count,
+        ));
+    }
+    if hidden_block_count > 0 {
+        result.push(terraform_hidden_line(
+            hidden_block_count,
+            "block",
+            indent_count,
+        ));
+    }
+    result
+}
+
+fn render_terraform_changed_array(
+    before: &[tf::Value],
+    after: &[tf::Value],
+    path: &[String],
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    let mut shown_count = 0;
+    let mut hidden_count = 0;
+    for op in options.diff_arrays(path, before, after) {
+        if options.is_ignored(&child_path(path, &array_diff_op_index(&op).to_string())) {
+            continue;
+        }
+        match op {
+            tf::ArrayDiffOp::Unchanged { after: index, .. } => {
+                shown_count += 1;
+                let exceeds_limit = options
+                    .limits
+                    .max_elements
+                    .is_some_and(|max| shown_count > max);
+                if options.show_changed_values && !exceeds_limit {
+                    result.extend(render_terraform_limited_value(
+                        " ",
+                        "",
+                        &after[index],
+                        ",",
+                        &child_path(path, &index.to_string()),
+                        indent_count,
+                        options,
+                    ));
+                } else {
+                    hidden_count += 1;
+                }
+            }
+            tf::ArrayDiffOp::Changed {
+                before: before_index,
+                after: after_index,
+            } => {
+                let before_value = &before[before_index];
+                let after_value = &after[after_index];
+                if is_nested(before_value) && is_nested(after_value) {
+                    result.extend(render_terraform_changed(
+                        "",
+                        before_value,
+                        after_value,
+                        ",",
+                        &child_path(path, &after_index.to_string()),
+                        indent_count,
+                        options,
+                    ));
+                } else {
+                    result.extend(render_terraform_limited_value(
+                        "-",
+                        "",
+                        before_value,
+                        ",",
+                        &child_path(path, &before_index.to_string()),
+                        indent_count,
+                        options,
+                    ));
+                    result.extend(render_terraform_limited_value(
+                        "+",
+                        "",
+                        after_value,
+                        ",",
+                        &child_path(path, &after_index.to_string()),
+                        indent_count,
+                        options,
+                    ));
+                }
+            }
+            tf::ArrayDiffOp::Removed { before: index } => {
+                result.extend(render_terraform_limited_value(
+                    "-",
+                    "",
+                    &before[index],
+                    ",",
+                    &child_path(path, &index.to_string()),
+                    indent_count,
+                    options,
+                ));
+            }
+            tf::ArrayDiffOp::Added { after: index } => {
+                result.extend(render_terraform_limited_value(
+                    "+",
+                    "",
+                    &after[index],
+                    ",",
+                    &child_path(path, &index.to_string()),
+                    indent_count,
+                    options,
+                ));
+            }
+        }
+    }
+    if hidden_count > 0 {
+        result.push(terraform_hidden_line(hidden_count, "element", indent_count));
+    }
+    result
+}
+
+fn render_terraform_changed_nested(
+    prefix: &str,
+    before_value: &tf::Value,
+    after_value: &tf::Value,
+    suffix: &str,
+    path: &[String],
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    match (before_value, after_value) {
+        _ if options.exceeds_depth(indent_count + 1) => vec![terraform_line(
+            "~",
+            indent_count,
+            &format!(
+                "{prefix}{} -> {}{suffix}",
+                render_collapsed(before_value),
+                render_collapsed(after_value)
+            ),
+        )],
+        (tf::Value::Object(before), tf::Value::Object(after)) => {
+            let mut result = vec![terraform_line("~", indent_count, &format!("{prefix}{{"))];
+            result.extend(render_terraform_changed_hashmap_value(
+                before,
+                after,
+                path,
+                indent_count + 1,
+                options,
+            ));
+            result.push(terraform_line(" ", indent_count, &format!("}}{suffix}")));
+            result
+        }
+        (tf::Value::Array(before), tf::Value::Array(after)) => {
+            let mut result = vec![terraform_line("~", indent_count, &format!("{prefix}["))];
+            result.extend(render_terraform_changed_array(
+                before,
+                after,
+                path,
+                indent_count + 1,
+                options,
+            ));
+            result.push(terraform_line(" ", indent_count, &format!("]{suffix}")));
+            result
+        }
+        _ => render_terraform_changed_plaintext(
+            prefix,
+            before_value,
+            after_value,
+            suffix,
+            indent_count,
+            options,
+        ),
+    }
+}
+
+/// Values known after apply are rendered like `terraform plan` does
+fn render_terraform_unknown(
+    prefix: &str,
+    before_value: &tf::Value,
+    suffix: &str,
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    match before_value {
+        tf::Value::Null => vec![terraform_line(
+            "+",
+            indent_count,
+            &format!("{prefix}(known after apply){suffix}"),
+        )],
+        _ => vec![terraform_line(
+            "~",
+            indent_count,
+            &format!(
+                "{prefix}{} -> (known after apply){suffix}",
+                render_terraform_plaintext(before_value, options)
+            ),
+        )],
+    }
+}
+
+fn render_terraform_changed(
+    prefix: &str,
+    before_value: &tf::Value,
+    after_value: &tf::Value,
+    suffix: &str,
+    path: &[String],
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    let node = options.diff_node(path);
+    let mut result = if node.is_some_and(|node| node.unknown) {
+        render_terraform_unknown(prefix, before_value, suffix, indent_count, options)
+    } else if options.is_sensitive_changed(path) {
+        vec![terraform_line(
+            "~",
+            indent_count,
+            &format!("{prefix}(sensitive value){suffix}"),
+        )]
+    } else {
+        render_terraform_changed_value(
+            prefix,
+            before_value,
+            after_value,
+            suffix,
+            path,
+            indent_count,
+            options,
+        )
+    };
+    if node.is_some_and(|node| node.forces_replacement && node.is_significant()) {
+        if let Some(first) = result.first_mut() {
+            first.push_str(" # forces replacement");
+        }
+    }
+    result
+}
+
+fn render_terraform_changed_value(
+    prefix: &str,
+    before_value: &tf::Value,
+    after_value: &tf::Value,
+    suffix: &str,
+    path: &[String],
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    match (before_value, after_value) {
+        _ if options.is_equivalent(path, before_value, after_value) => {
+            render_terraform_limited_value(
+                " ",
+                prefix,
+                after_value,
+                suffix,
+                path,
+                indent_count,
+                options,
+            )
+        }
+        (tf::Value::Null, _) => render_terraform_limited_value(
+            "+",
+            prefix,
+            after_value,
+            suffix,
+            path,
+            indent_count,
+            options,
+        ),
+        (_, tf::Value::Null) => render_terraform_limited_value(
+            "-",
+            prefix,
+            before_value,
+            &format!(" -> null{suffix}"),
+            path,
+            indent_count,
+            options,
+        ),
+        (
+            tf::Value::Object(_) | tf::Value::Array(_),
+            tf::Value::Object(_) | tf::Value::Array(_),
+        ) => render_terraform_changed_nested(
+            prefix,
+            before_value,
+            after_value,
+            suffix,
+            path,
+            indent_count,
+            options,
+        ),
+        (tf::Value::String(before), tf::Value::String(after)) => {
+            match (
+                tf::Value::from_json_string(before),
+                tf::Value::from_json_string(after),
+            ) {
+                (Some(before), Some(after)) if before != after => {
+                    let mut result = vec![terraform_line(
+                        "~",
+                        indent_count,
+                        &format!("{prefix}jsonencode("),
+                    )];
+                    result.extend(render_terraform_changed(
+                        "",
+                        &before,
+                        &after,
+                        "",
+                        path,
+                        indent_count + 1,
+                        &options.decoded(),
+                    ));
+                    result.push(terraform_line(" ", indent_count, &format!("){suffix}")));
+                    result
+                }
+                _ => render_terraform_changed_plaintext(
+                    prefix,
+                    before_value,
+                    after_value,
+                    suffix,
+                    indent_count,
+                    options,
+                ),
+            }
+        }
+        (_, _) => render_terraform_changed_plaintext(
+            prefix,
+            before_value,
+            after_value,
+            suffix,
+            indent_count,
+            options,
+        ),
+    }
+}
+
+fn render_terraform_changed_plaintext(
+    prefix: &str,
+    before_value: &tf::Value,
+    after_value: &tf::Value,
+    suffix: &str,
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    let before = render_terraform_plaintext(before_value, options);
+    let after = render_terraform_plaintext(after_value, options);
+    // Truncated values can look equal, the change is still reported
+    let note = if before == after && before_value != after_value {
+        " # changed beyond truncation"
+    } else {
+        ""
+    };
+    vec![terraform_line(
+        "~",
+        indent_count,
+        &format!("{prefix}{before} -> {after}{suffix}{note}"),
+    )]
+}
+
+fn render_terraform_values(
+    before: Option<&tf::ValueMap>,
+    after: Option<&tf::ValueMap>,
+    indent_count: usize,
+    options: &ValuesOptions,
+) -> Vec<String> {
+    let empty = tf::ValueMap::new();
+    render_terraform_changed_hashmap_value(
+        before.unwrap_or(&empty),
+        after.unwrap_or(&empty),
+        &[],
+        indent_count,
+        options,
+    )
+}
+
+fn render_terraform(change: &tf::Change, options: &ValuesOptions) -> Vec<String> {
+    let (sign, description) = match change.action {
+        tf::Action::Create => ("+", "will be created"),
+        tf::Action::Delete => ("-", "will be destroyed"),
+        tf::Action::DeleteCreate => ("-/+", "must be replaced"),
+        tf::Action::Update => ("~", "will be updated in-place"),
+        tf::Action::Read => ("<=", "will be read during apply"),
+        tf::Action::NoOp => (" ", "has no changes"),
+        tf::Action::Unknown => ("?", "has unknown changes"),
+    };
+    let kind = if change.mode == "data" {
+        "data"
+    } else {
+        "resource"
+    };
+
+    // Signs are right-aligned as in `terraform plan`, so that all headers line up
+    let mut result: Vec<String> = Vec::new();
+    result.push(terraform_line(
+        " ",
+        0,
+        &format!("# {} {description}", change.address),
+    ));
+    result.push(format!(
+        "{sign:>3} {kind} \"{}\" \"{}\" {{",
+        change.type_, change.name
+    ));
+    result.extend(render_terraform_values(
+        change.before.as_ref(),
+        change.after.as_ref(),
+        2,
+        options,
+    ));
+    result.push(terraform_line(" ", 1, "}"));
+    result
+}
+
+/// Renders the change like `terraform plan`, used by outputs without templates
+#[must_use]
+pub fn render_terraform_change(
+    change: &tf::Change,
+    schemas: &schema::Registry,
+    normalize_empty: bool,
+    limits: &ValuesLimits,
+) -> String {
+    let options = ValuesOptions {
+        show_changed_values: DEFAULT_SHOW_CHANGED_VALUES,
+        ignored_paths: change.ignored_paths.clone(),
+        normalize_empty,
+        schema: schemas.get(&change.mode, &change.type_),
+        limits: limits.clone(),
+        indent_base: 2,
+        diff: change.diff.clone(),
+    };
+    render_terraform(change, &options).join("\n")
+}
+
+fn render_terraform_args(args: &Args, schemas: &schema::Registry) -> tera::Result<tera::Value> {
+    let change = args.get("change").ok_or("change must be present in args")?;
+    let change = tera::from_value::<tf::Change>(change.clone())?;
+    let mut options = ValuesOptions::from_args(args, schemas)?;
+    options
+        .ignored_paths
+        .extend(change.ignored_paths.iter().cloned());
+    options.schema = schemas.get(&change.mode, &change.type_);
+    options.indent_base = 2;
+    options.diff.clone_from(&change.diff);
+
+    let result = render_terraform(&change, &options);
+    Ok(tera::Value::String(result.join("\n")))
+}
+
+fn tera_render_terraform(schemas: Arc<schema::Registry>) -> impl tera::Function {
+    move |args: &Args| render_terraform_args(args, &schemas)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ValuesStyle {
+    #[default]
+    Default,
+    Terraform,
+}
+
+impl FromStr for ValuesStyle {
+    type Err = types::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(ValuesStyle::Default),
+            "terraform" => Ok(ValuesStyle::Terraform),
+            _ => Err(types::Error::default(format!("Invalid values style: {s}"))),
+        }
+    }
+}
+
+impl ValuesStyle {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            ValuesStyle::Default => "default",
+            ValuesStyle::Terraform => "terraform",
+        }
+    }
+}
+
+fn render_values_args(args: &Args, schemas: &schema::Registry) -> tera::Result<tera::Value> {
     let before = args.get("before").ok_or("before must be present in args")?;
     let after = args.get("after").ok_or("after must be present in args")?;
-    let show_changed_values = args
-        .get("show_changed_values")
-        .unwrap_or(&tera::Value::Bool(DEFAULT_SHOW_CHANGED_VALUES));
+    let style = match args.get("style") {
+        Some(style) => ValuesStyle::from_str(&tera::from_value::<String>(style.clone())?)
+            .map_err(|e| tera::Error::msg(e.to_string()))?,
+        None => ValuesStyle::Default,
+    };
 
     let before = tera::from_value::<Option<tf::ValueMap>>(before.clone())?;
     let after = tera::from_value::<Option<tf::ValueMap>>(after.clone())?;
-    let show_changed_values = tera::from_value::<bool>(show_changed_values.clone())?;
+    let options = ValuesOptions::from_args(args, schemas)?;
 
-    match (before, after, show_changed_values) {
-        (Some(before), Some(after), _) => {
-            let result = render_changed_hashmap_value(&before, &after, 0, show_changed_values);
+    if style == ValuesStyle::Terraform {
+        let result = render_terraform_values(before.as_ref(), after.as_ref(), 0, &options);
+        return Ok(tera::Value::String(result.join("\n")));
+    }
+
+    match (before, after) {
+        (Some(before), Some(after)) => {
+            let result = render_changed_hashmap_value(&before, &after, &[], 0, &options);
             Ok(tera::Value::String(result.join("\n")))
         }
-        (Some(before), None, _) => {
-            let result = render_unchanged_hashmap_value(&before, 0);
+        (Some(before), None) => {
+            let result = render_unchanged_values(&before, &options);
             Ok(tera::Value::String(result.join("\n")))
         }
-        (None, Some(after), _) => {
-            let result = render_unchanged_hashmap_value(&after, 0);
+        (None, Some(after)) => {
+            let result = render_unchanged_values(&after, &options);
             Ok(tera::Value::String(result.join("\n")))
         }
         _ => Ok(tera::Value::String(String::new())),
     }
 }
 
+/// Provider schemas are looked up by the `mode` and `type` args instead of being passed in the context
+fn tera_render_values(schemas: Arc<schema::Registry>) -> impl tera::Function {
+    move |args: &Args| render_values_args(args, &schemas)
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum RenderOptionValue {
     Bool(bool),
+    Integer(usize),
     String(String),
 }
 
@@ -250,13 +1850,27 @@ pub fn render(
     template: &str,
     options: Option<RenderOptions>,
 ) -> Result<String, types::Error> {
+    let options = options.unwrap_or_default();
+    let symbols = match options.get("symbols") {
+        Some(RenderOptionValue::String(symbols)) => ActionSymbols::from_str(symbols)?,
+        _ => ActionSymbols::default(),
+    };
+
     let mut tera = tera::Tera::default();
-    tera.register_function("render_values", tera_render_values);
-    tera.register_function("render_action", tera_render_action);
-    tera.register_function("render_actions", tera_render_actions);
+    tera.register_function("render_values", tera_render_values(data.schemas.clone()));
+    tera.register_function("render_action", tera_render_action(symbols.clone()));
+    tera.register_function("render_actions", tera_render_actions(symbols));
+    tera.register_function(
+        "render_terraform",
+        tera_render_terraform(data.schemas.clone()),
+    );
+    tera.register_function("render_value", tera_render_value);
 
     let template_name = "template";
-    match tera.add_raw_template(template_name, template) {
+    match tera.add_raw_templates(vec![
+        ("markdown", MARKDOWN_MACROS),
+        (template_name, template),
+    ]) {
         Ok(()) => {}
         Err(e) => {
             return Err(types::Error::chain(
@@ -268,7 +1882,6 @@ pub fn render(
 
     let mut context = tera::Context::new();
     context.insert("data", &data);
-    let options = options.unwrap_or_default();
     context.insert("options", &options);
 
     match tera.render(template_name, &context) {
@@ -287,39 +1900,149 @@ mod tests {
     mod render_action {
         use super::*;
 
-        fn test_with_context(context: tera::Context) -> tera::Result<String> {
+        fn test_with_context(context: tera::Context) -> tera::Result<String> {
+            let mut tera = tera::Tera::default();
+            tera.register_function(
+                "render_action",
+                tera_render_action(ActionSymbols::default()),
+            );
+
+            tera.add_raw_template("template", "{{ render_action(action=action) }}")
+                .unwrap();
+
+            tera.render("template", &context)
+        }
+
+        fn test(action: tf::Action) -> tera::Result<String> {
+            let mut context = tera::Context::new();
+            context.insert("action", &action);
+
+            test_with_context(context)
+        }
+
+        #[test]
+        fn options() {
+            assert_eq!(test(tf::Action::Create).unwrap(), "✅");
+            assert_eq!(test(tf::Action::Delete).unwrap(), "❌");
+            assert_eq!(test(tf::Action::DeleteCreate).unwrap(), "♻️");
+            assert_eq!(test(tf::Action::Update).unwrap(), "🔄");
+            assert_eq!(test(tf::Action::NoOp).unwrap(), "🟰");
+            assert_eq!(test(tf::Action::Read).unwrap(), "🔍");
+            assert_eq!(test(tf::Action::Unknown).unwrap(), "❓");
+        }
+
+        #[test]
+        fn shortcodes() {
+            let mut tera = tera::Tera::default();
+            tera.register_function(
+                "render_action",
+                tera_render_action(ActionSymbols::default()),
+            );
+            tera.register_function(
+                "render_actions",
+                tera_render_actions(ActionSymbols::default()),
+            );
+            tera.add_raw_template(
+                "template",
+                "{{ render_action(action='Create', symbols='shortcodes') }}{{ render_actions(actions=['Update', 'Delete'], symbols='shortcodes') }}",
+            )
+            .unwrap();
+
+            assert_eq!(
+                tera.render("template", &tera::Context::new()).unwrap(),
+                ":white_check_mark: :arrows_counterclockwise: :x: "
+            );
+        }
+
+        #[test]
+        fn ascii() {
+            let actions = [
+                tf::Action::Create,
+                tf::Action::Delete,
+                tf::Action::DeleteCreate,
+                tf::Action::Update,
+                tf::Action::NoOp,
+                tf::Action::Read,
+                tf::Action::Unknown,
+            ];
+            let result: Vec<String> = actions
+                .iter()
+                .map(|action| render_action(action, &ActionSymbols::Ascii))
+                .collect();
+
+            assert_eq!(result, vec!["+ ", "- ", "-/+ ", "~ ", "= ", "<= ", "? "]);
+        }
+
+        #[test]
+        fn words() {
+            assert_eq!(
+                render_action(&tf::Action::Create, &ActionSymbols::Words),
+                "[create] "
+            );
+            assert_eq!(
+                render_action(&tf::Action::DeleteCreate, &ActionSymbols::Words),
+                "[replace] "
+            );
+        }
+
+        #[test]
+        fn default_symbols() {
             let mut tera = tera::Tera::default();
-            tera.register_function("render_action", tera_render_action);
-
-            tera.add_raw_template("template", "{{ render_action(action=action) }}")
-                .unwrap();
+            tera.register_function("render_action", tera_render_action(ActionSymbols::Words));
+            tera.register_function("render_actions", tera_render_actions(ActionSymbols::Ascii));
+            tera.add_raw_template(
+                "template",
+                "{{ render_action(action='Delete') }}{{ render_actions(actions=['Update']) }}{{ render_action(action='Delete', symbols='emoji') }}",
+            )
+            .unwrap();
 
-            tera.render("template", &context)
+            assert_eq!(
+                tera.render("template", &tera::Context::new()).unwrap(),
+                "[delete] ~ ❌"
+            );
         }
 
-        fn test(action: tf::Action) -> tera::Result<String> {
-            let mut context = tera::Context::new();
-            context.insert("action", &action);
+        #[test]
+        fn markdown() {
+            let mut tera = tera::Tera::default();
+            tera.register_function("render_action", tera_render_action(ActionSymbols::Ascii));
+            tera.register_function("render_actions", tera_render_actions(ActionSymbols::Ascii));
+            tera.add_raw_template(
+                "template",
+                "{{ render_action(action='Delete', markdown=true) }}{{ render_actions(actions=['Update'], markdown=true) }}{{ render_action(action='Delete', symbols='emoji', markdown=true) }}",
+            )
+            .unwrap();
 
-            test_with_context(context)
+            assert_eq!(
+                tera.render("template", &tera::Context::new()).unwrap(),
+                "`-` `~` ❌"
+            );
         }
 
         #[test]
-        fn options() {
-            assert_eq!(test(tf::Action::Create).unwrap(), "✅");
-            assert_eq!(test(tf::Action::Delete).unwrap(), "❌");
-            assert_eq!(test(tf::Action::DeleteCreate).unwrap(), "♻️");
-            assert_eq!(test(tf::Action::Update).unwrap(), "🔄");
-            assert_eq!(test(tf::Action::NoOp).unwrap(), "🟰");
-            assert_eq!(test(tf::Action::Read).unwrap(), "🔍");
-            assert_eq!(test(tf::Action::Unknown).unwrap(), "❓");
+        fn invalid_symbols() {
+            let mut tera = tera::Tera::default();
+            tera.register_function(
+                "render_action",
+                tera_render_action(ActionSymbols::default()),
+            );
+            tera.add_raw_template(
+                "template",
+                "{{ render_action(action='Create', symbols='invalid') }}",
+            )
+            .unwrap();
+
+            tera.render("template", &tera::Context::new()).unwrap_err();
         }
 
         #[test]
         fn not_in_args() {
             let context = tera::Context::new();
             let mut tera = tera::Tera::default();
-            tera.register_function("render_action", tera_render_action);
+            tera.register_function(
+                "render_action",
+                tera_render_action(ActionSymbols::default()),
+            );
             tera.add_raw_template("template", "{{ render_action() }}")
                 .unwrap();
 
@@ -341,7 +2064,10 @@ mod tests {
 
         fn test_with_context(context: tera::Context) -> tera::Result<String> {
             let mut tera = tera::Tera::default();
-            tera.register_function("render_actions", tera_render_actions);
+            tera.register_function(
+                "render_actions",
+                tera_render_actions(ActionSymbols::default()),
+            );
 
             tera.add_raw_template("template", "{{ render_actions(actions=actions) }}")
                 .unwrap();
@@ -380,7 +2106,10 @@ mod tests {
         fn not_in_args() {
             let context = tera::Context::new();
             let mut tera = tera::Tera::default();
-            tera.register_function("render_actions", tera_render_actions);
+            tera.register_function(
+                "render_actions",
+                tera_render_actions(ActionSymbols::default()),
+            );
             tera.add_raw_template("template", "{{ render_actions() }}")
                 .unwrap();
 
@@ -402,7 +2131,7 @@ mod tests {
 
         fn test_with_context(context: tera::Context) -> tera::Result<String> {
             let mut tera = tera::Tera::default();
-            tera.register_function("render_values", tera_render_values);
+            tera.register_function("render_values", tera_render_values(Arc::default()));
 
             tera.add_raw_template(
                 "template",
@@ -481,7 +2210,8 @@ mod tests {
         fn before_after() {
             let result = test(Some(get_test_data()), Some(get_another_test_data())).unwrap();
 
-            let expected = r#"array: [42] -> [43]
+            let expected = r#"array:
+  [0]: 42 -> 43
 bool: true -> false
 float: 42.1 -> 43.1
 integer: 42 -> 43
@@ -532,6 +2262,581 @@ string: "string""#;
             pretty_assertions::assert_eq!(result, expected);
         }
 
+        #[test]
+        fn json_string() {
+            let mut before = tf::ValueMap::new();
+            before.insert(
+                "policy".to_string(),
+                tf::Value::String(r#"{"Effect": "Allow", "Action": "s3:GetObject"}"#.to_string()),
+            );
+            before.insert(
+                "formatting".to_string(),
+                tf::Value::String(r#"{"key": "value"}"#.to_string()),
+            );
+            before.insert(
+                "not_json".to_string(),
+                tf::Value::String("{not json".to_string()),
+            );
+            let mut after = tf::ValueMap::new();
+            after.insert(
+                "policy".to_string(),
+                tf::Value::String(r#"{"Effect": "Deny", "Action": "s3:GetObject"}"#.to_string()),
+            );
+            after.insert(
+                "formatting".to_string(),
+                tf::Value::String(r#"{"key":"value"}"#.to_string()),
+            );
+            after.insert(
+                "not_json".to_string(),
+                tf::Value::String(r#"{"key": "value"}"#.to_string()),
+            );
+
+            let result = test(Some(before), Some(after)).unwrap();
+
+            let expected = r#"formatting: "{\"key\": \"value\"}" -> "{\"key\":\"value\"}"
+not_json: "{not json" -> "{\"key\": \"value\"}"
+policy (json):
+  Action: "s3:GetObject"
+  Effect: "Allow" -> "Deny""#;
+            pretty_assertions::assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn array() {
+            let rule = |port: i64| {
+                let mut map = tf::ValueMap::new();
+                map.insert("port".to_string(), tf::Value::Integer(port));
+                tf::Value::Object(map)
+            };
+            let mut before = tf::ValueMap::new();
+            before.insert(
+                "reordered".to_string(),
+                tf::Value::Array(vec![
+                    tf::Value::String("a".to_string()),
+                    tf::Value::String("b".to_string()),
+                    tf::Value::String("c".to_string()),
+                ]),
+            );
+            before.insert(
+                "rules".to_string(),
+                tf::Value::Array(vec![rule(22), rule(80), rule(443)]),
+            );
+            let mut after = tf::ValueMap::new();
+            after.insert(
+                "reordered".to_string(),
+                tf::Value::Array(vec![
+                    tf::Value::String("b".to_string()),
+                    tf::Value::String("c".to_string()),
+                    tf::Value::String("a".to_string()),
+                ]),
+            );
+            after.insert(
+                "rules".to_string(),
+                tf::Value::Array(vec![rule(22), rule(8080), rule(443), rule(8443)]),
+            );
+
+            let result = test(Some(before), Some(after)).unwrap();
+
+            let expected = r#"reordered:
+  (removed, was [0]): "a"
+  [0]: "b"
+  [1]: "c"
+  [2] (added): "a"
+rules:
+  [0]:
+    port: 22
+  [1]:
+    port: 80 -> 8080
+  [2]:
+    port: 443
+  [3] (added):
+    port: 8443"#;
+            pretty_assertions::assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn array_of_objects() {
+            let block = |port: i64| {
+                let mut map = tf::ValueMap::new();
+                map.insert("port".to_string(), tf::Value::Integer(port));
+                map.insert(
+                    "cidr_blocks".to_string(),
+                    tf::Value::Array(vec![tf::Value::String("0.0.0.0/0".to_string())]),
+                );
+                tf::Value::Object(map)
+            };
+            let mut before = tf::ValueMap::new();
+            before.insert("egress".to_string(), tf::Value::Array(vec![block(0)]));
+            before.insert("ingress".to_string(), tf::Value::Null);
+            before.insert(
+                "mixed".to_string(),
+                tf::Value::Array(vec![tf::Value::Integer(1)]),
+            );
+            let mut after = tf::ValueMap::new();
+            after.insert("egress".to_string(), tf::Value::Null);
+            after.insert(
+                "ingress".to_string(),
+                tf::Value::Array(vec![block(22), block(443)]),
+            );
+            after.insert(
+                "mixed".to_string(),
+                tf::Value::Array(vec![tf::Value::Integer(1), block(80)]),
+            );
+
+            let result = test(Some(before), Some(after.clone())).unwrap();
+
+            let expected = r#"egress:
+  [0]:
+    cidr_blocks: ["0.0.0.0/0"]
+    port: 0
+ingress:
+  [0]:
+    cidr_blocks: ["0.0.0.0/0"]
+    port: 22
+  [1]:
+    cidr_blocks: ["0.0.0.0/0"]
+    port: 443
+mixed:
+  [0]: 1
+  [1] (added):
+    cidr_blocks: ["0.0.0.0/0"]
+    port: 80"#;
+            pretty_assertions::assert_eq!(result, expected);
+
+            let result = test(None, Some(after)).unwrap();
+
+            let expected = r#"egress: null
+ingress:
+  [0]:
+    cidr_blocks: ["0.0.0.0/0"]
+    port: 22
+  [1]:
+    cidr_blocks: ["0.0.0.0/0"]
+    port: 443
+mixed:
+  [0]: 1
+  [1]:
+    cidr_blocks: ["0.0.0.0/0"]
+    port: 80"#;
+            pretty_assertions::assert_eq!(result, expected);
+        }
+
+        fn test_terraform(
+            before: Option<&tf::ValueMap>,
+            after: Option<&tf::ValueMap>,
+        ) -> tera::Result<String> {
+            let mut context = tera::Context::new();
+            context.insert("before", &before);
+            context.insert("after", &after);
+
+            let mut tera = tera::Tera::default();
+            tera.register_function("render_values", tera_render_values(Arc::default()));
+            tera.add_raw_template(
+                "template",
+                "{{ render_values(before=before, after=after, show_changed_values=false, style='terraform') }}",
+            )
+            .unwrap();
+
+            tera.render("template", &context)
+        }
+
+        #[test]
+        fn terraform_style() {
+            let mut before = get_test_data();
+            before.insert(
+                "list".to_string(),
+                tf::Value::Array(vec![
+                    tf::Value::String("a".to_string()),
+                    tf::Value::String("b".to_string()),
+                    tf::Value::String("c".to_string()),
+                ]),
+            );
+            before.insert(
+                "policy".to_string(),
+                tf::Value::String(r#"{"Effect": "Allow", "Action": "s3:GetObject"}"#.to_string()),
+            );
+            let mut after = get_another_test_data();
+            after.insert(
+                "list".to_string(),
+                tf::Value::Array(vec![
+                    tf::Value::String("a".to_string()),
+                    tf::Value::String("x".to_string()),
+                    tf::Value::String("c".to_string()),
+                ]),
+            );
+            after.insert(
+                "policy".to_string(),
+                tf::Value::String(r#"{"Effect": "Deny", "Action": "s3:GetObject"}"#.to_string()),
+            );
+
+            let result = test_terraform(Some(&before), Some(&after)).unwrap();
+
+            let expected = r#"~ array          = [
+-   42,
++   43,
+  ]
+~ bool           = true -> false
+~ float          = 42.1 -> 43.1
+~ integer        = 42 -> 43
+~ list           = [
+-   "b",
++   "x",
+    # (2 unchanged elements hidden)
+  ]
++ null_to_object = {
++   inner_integer = 43
++ }
+~ object         = {
+~   inner_integer = 42 -> 43
+  }
+- object_to_null = {
+-   inner_integer = 42
+- } -> null
+~ policy         = jsonencode(
+~   {
+~     Effect = "Allow" -> "Deny"
+      # (1 unchanged attribute hidden)
+    }
+  )
+~ string         = "string" -> "another string""#;
+            pretty_assertions::assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn json_string_without_diff() {
+            let policy = |effect: &str| {
+                tf::ValueMap::from([(
+                    "policy".to_string(),
+                    tf::Value::String(format!(r#"{{"Effect": "{effect}"}}"#)),
+                )])
+            };
+            let node = |path: &[&str], unknown: bool, children: serde_json::Value| {
+                serde_json::json!({
+                    "path": path,
+                    "kind": "changed",
+                    "before": null,
+                    "after": null,
+                    "sensitive": unknown,
+                    "unknown": unknown,
+                    "forces_replacement": false,
+                    "ignored": false,
+                    "cosmetic": false,
+                    "description": null,
+                    "children": children
+                })
+            };
+            // Markers at paths of the resource that collide with keys of the decoded document
+            let diff = serde_json::json!([node(
+                &["policy"],
+                false,
+                serde_json::json!([node(&["policy", "Effect"], true, serde_json::json!([]))])
+            )]);
+            let mut context = tera::Context::new();
+            context.insert("before", &Some(policy("Allow")));
+            context.insert("after", &Some(policy("Deny")));
+            context.insert("diff", &diff);
+
+            let mut tera = tera::Tera::default();
+            tera.register_function("render_values", tera_render_values(Arc::default()));
+            tera.add_raw_template(
+                "values",
+                "{{ render_values(before=before, after=after, show_changed_values=false, diff=diff) }}",
+            )
+            .unwrap();
+            tera.add_raw_template(
+                "terraform",
+                "{{ render_values(before=before, after=after, show_changed_values=false, diff=diff, style='terraform') }}",
+            )
+            .unwrap();
+
+            pretty_assertions::assert_eq!(
+                tera.render("values", &context).unwrap(),
+                r#"policy (json):
+  Effect: "Allow" -> "Deny""#
+            );
+            pretty_assertions::assert_eq!(
+                tera.render("terraform", &context).unwrap(),
+                r#"~ policy = jsonencode(
+~   {
+~     Effect = "Allow" -> "Deny"
+    }
+  )"#
+            );
+        }
+
+        #[test]
+        fn ignored_paths() {
+            let mut context = tera::Context::new();
+            context.insert("before", &Some(get_test_data()));
+            context.insert("after", &Some(get_another_test_data()));
+            context.insert(
+                "ignored_paths",
+                &vec![
+                    vec!["string".to_string()],
+                    vec!["object".to_string(), "inner_integer".to_string()],
+                    vec!["array".to_string(), "*".to_string()],
+                ],
+            );
+
+            let mut tera = tera::Tera::default();
+            tera.register_function("render_values", tera_render_values(Arc::default()));
+            tera.add_raw_template(
+                "template",
+                "{{ render_values(before=before, after=after, show_changed_values=false, ignored_paths=ignored_paths) }}",
+            )
+            .unwrap();
+
+            let result = tera.render("template", &context).unwrap();
+
+            let expected = "bool: true -> false
+float: 42.1 -> 43.1
+integer: 42 -> 43
+null_to_object:
+  inner_integer: 43
+object_to_null:
+  inner_integer: 42";
+            pretty_assertions::assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn normalize_empty() {
+            let mut before = tf::ValueMap::new();
+            before.insert("string".to_string(), tf::Value::String(String::new()));
+            before.insert("array".to_string(), tf::Value::Array(Vec::new()));
+            before.insert("object".to_string(), tf::Value::Null);
+            before.insert("changed".to_string(), tf::Value::String(String::new()));
+            let mut after = tf::ValueMap::new();
+            after.insert("string".to_string(), tf::Value::Null);
+            after.insert("array".to_string(), tf::Value::Null);
+            after.insert("object".to_string(), tf::Value::Object(tf::ValueMap::new()));
+            after.insert(
+                "changed".to_string(),
+                tf::Value::String("value".to_string()),
+            );
+
+            let mut context = tera::Context::new();
+            context.insert("before", &Some(before));
+            context.insert("after", &Some(after));
+
+            let mut tera = tera::Tera::default();
+            tera.register_function("render_values", tera_render_values(Arc::default()));
+            tera.add_raw_template(
+                "default",
+                "{{ render_values(before=before, after=after, show_changed_values=false, normalize_empty=true) }}",
+            )
+            .unwrap();
+            tera.add_raw_template(
+                "terraform",
+                "{{ render_values(before=before, after=after, show_changed_values=false, normalize_empty=true, style='terraform') }}",
+            )
+            .unwrap();
+
+            let result = tera.render("default", &context).unwrap();
+            pretty_assertions::assert_eq!(result, r#"changed: "" -> "value""#);
+
+            let result = tera.render("terraform", &context).unwrap();
+            let expected = r#"~ changed = "" -> "value"
+  # (3 unchanged attributes hidden)"#;
+            pretty_assertions::assert_eq!(result, expected);
+        }
+
+        fn get_limits_test_data() -> (tf::ValueMap, tf::ValueMap) {
+            let object = |pairs: &[(&str, i64)]| {
+                tf::Value::Object(
+                    pairs
+                        .iter()
+                        .map(|(key, value)| (key.to_string(), tf::Value::Integer(*value)))
+                        .collect(),
+                )
+            };
+            let mut before = tf::ValueMap::new();
+            before.insert("added".to_string(), tf::Value::Null);
+            before.insert(
+                "code".to_string(),
+                tf::Value::String("aaaaaaaaaa1".to_string()),
+            );
+            before.insert(
+                "list".to_string(),
+                tf::Value::Array(vec![
+                    object(&[("a", 1)]),
+                    object(&[("a", 2)]),
+                    object(&[("a", 3)]),
+                    object(&[("a", 4)]),
+                ]),
+            );
+            let mut nested = tf::ValueMap::new();
+            nested.insert("inner".to_string(), object(&[("deep", 1)]));
+            before.insert("nested".to_string(), tf::Value::Object(nested.clone()));
+
+            let mut after = before.clone();
+            after.insert("added".to_string(), object(&[("x", 1), ("y", 2), ("z", 3)]));
+            after.insert(
+                "code".to_string(),
+                tf::Value::String("aaaaaaaaaa2".to_string()),
+            );
+            after.insert(
+                "list".to_string(),
+                tf::Value::Array(vec![
+                    object(&[("a", 1)]),
+                    object(&[("a", 2)]),
+                    object(&[("a", 3)]),
+                    object(&[("a", 5)]),
+                ]),
+            );
+            nested.insert("inner".to_string(), object(&[("deep", 2)]));
+            after.insert("nested".to_string(), tf::Value::Object(nested));
+            (before, after)
+        }
+
+        #[test]
+        fn limits() {
+            let (before, after) = get_limits_test_data();
+            let mut context = tera::Context::new();
+            context.insert("before", &Some(before));
+            context.insert("after", &Some(after));
+
+            let mut tera = tera::Tera::default();
+            tera.register_function("render_values", tera_render_values(Arc::default()));
+            let args = "before=before, after=after, max_string_length=5, max_lines=2, max_depth=2, max_elements=2";
+            tera.add_raw_template("default", &format!("{{{{ render_values({args}) }}}}"))
+                .unwrap();
+            tera.add_raw_template(
+                "terraform",
+                &format!("{{{{ render_values({args}, style='terraform') }}}}"),
+            )
+            .unwrap();
+
+            let result = tera.render("default", &context).unwrap();
+            let expected = r#"added:
+  x: 1
+  … (2 more lines)
+code: "aaaaa"… (6 more characters) -> "aaaaa"… (6 more characters) (changed beyond truncation)
+list:
+  [0]: {… (1 more key)}
+  [1]: {… (1 more key)}
+  [3]: {… (1 more key)} -> {… (1 more key)}
+  … (1 more element)
+nested:
+  inner: {… (1 more key)} -> {… (1 more key)}"#;
+            pretty_assertions::assert_eq!(result, expected);
+
+            let result = tera.render("terraform", &context).unwrap();
+            let expected = r#"+ added  = {
++   x = 1
++   … (2 more lines)
++ }
+~ code   = "aaaaa"… (6 more characters) -> "aaaaa"… (6 more characters) # changed beyond truncation
+~ list   = [
+    {… (1 more key)},
+    {… (1 more key)},
+~   {… (1 more key)} -> {… (1 more key)},
+    # (1 unchanged element hidden)
+  ]
+~ nested = {
+~   inner = {… (1 more key)} -> {… (1 more key)}
+  }"#;
+            pretty_assertions::assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn zero_limits() {
+            let (before, after) = get_limits_test_data();
+            let mut context = tera::Context::new();
+            context.insert("before", &Some(before.clone()));
+            context.insert("after", &Some(after.clone()));
+
+            let mut tera = tera::Tera::default();
+            tera.register_function("render_values", tera_render_values(Arc::default()));
+            tera.add_raw_template(
+                "template",
+                "{{ render_values(before=before, after=after, max_string_length=0, max_lines=0, max_depth=0, max_elements=0) }}",
+            )
+            .unwrap();
+
+            let result = tera.render("template", &context).unwrap();
+            pretty_assertions::assert_eq!(result, test(Some(before), Some(after)).unwrap());
+        }
+
+        #[test]
+        fn numbers() {
+            let mut before = tf::ValueMap::new();
+            before.insert(
+                "id".to_string(),
+                tf::Value::Number("123456789012345678901234567890".to_string()),
+            );
+            before.insert("ratio".to_string(), tf::Value::Number("1.50".to_string()));
+            let mut after = before.clone();
+            after.insert(
+                "id".to_string(),
+                tf::Value::Number("123456789012345678901234567891".to_string()),
+            );
+
+            let result = test(Some(before), Some(after)).unwrap();
+            let expected = "id: 123456789012345678901234567890 -> 123456789012345678901234567891
+ratio: 1.50";
+            pretty_assertions::assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn schema() {
+            let json =
+                crate::utils::test::get_test_data_file_contents("plans/artificial/schema.json");
+            let raw = tf::RawPlan::from_str(&json).unwrap();
+            let raw_change = raw.resource_changes.unwrap().remove(0);
+            let path = crate::utils::test::get_test_data_file_path("schemas/providers.json");
+            let schemas = schema::Registry::from_file(&path).unwrap();
+            let options = tf::Options {
+                schemas: schemas.clone(),
+                ..tf::Options::default()
+            };
+            let change = tf::Change::from_raw(raw_change, &options);
+
+            let mut context = tera::Context::new();
+            context.insert("change", &change);
+
+            let mut tera = tera::Tera::default();
+            tera.register_function("render_values", tera_render_values(Arc::new(schemas)));
+            tera.add_raw_template(
+                "template",
+                "{{ render_values(before=change.before, after=change.after, show_changed_values=false, mode=change.mode, type=change.type, diff=change.diff) }}",
+            )
+            .unwrap();
+
+            let result = tera.render("template", &context).unwrap();
+
+            let expected = r#"endpoints:
+  [0]:
+    token: (sensitive value) (changed)
+    url: "https://old.example.com" -> "https://new.example.com"
+ingress:
+  (removed, was [0]):
+    cidr_blocks: ["0.0.0.0/0"]
+    port: 80
+  [1] (added):
+    cidr_blocks: ["10.0.0.0/8"]
+    port: 8080
+name: "old" -> "new"
+password: (sensitive value) (changed)
+timeouts:
+  create: "5m" -> "10m""#;
+            pretty_assertions::assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn invalid_style() {
+            let mut tera = tera::Tera::default();
+            tera.register_function("render_values", tera_render_values(Arc::default()));
+            tera.add_raw_template(
+                "template",
+                "{{ render_values(before=none, after=none, style='invalid') }}",
+            )
+            .unwrap();
+            let mut context = tera::Context::new();
+            context.insert("none", &Option::<tf::ValueMap>::None);
+
+            tera.render("template", &context).unwrap_err();
+        }
+
         #[test]
         fn none() {
             let result = test(None, None).unwrap();
@@ -542,7 +2847,7 @@ string: "string""#;
         fn not_in_args() {
             let context = tera::Context::new();
             let mut tera = tera::Tera::default();
-            tera.register_function("render_values", tera_render_values);
+            tera.register_function("render_values", tera_render_values(Arc::default()));
             tera.add_raw_template("template", "{{ render_values() }}")
                 .unwrap();
 
@@ -559,6 +2864,97 @@ string: "string""#;
         }
     }
 
+    mod render_terraform {
+        use super::*;
+
+        fn test_with_context(context: &tera::Context) -> tera::Result<String> {
+            let mut tera = tera::Tera::default();
+            tera.register_function("render_terraform", tera_render_terraform(Arc::default()));
+
+            tera.add_raw_template(
+                "template",
+                "{{ render_terraform(change=change, show_changed_values=show_changed_values) }}",
+            )
+            .unwrap();
+
+            tera.render("template", context)
+        }
+
+        fn test(plan_type: &tf::tests::PlanType, show_changed_values: bool) -> String {
+            let json = tf::tests::get_test_plan_json(plan_type);
+            let raw = tf::RawPlan::from_str(&json).unwrap();
+            let raw_change = raw.resource_changes.unwrap().remove(0);
+            let change = tf::Change::from_raw(raw_change, &tf::Options::default());
+
+            let mut context = tera::Context::new();
+            context.insert("change", &change);
+            context.insert("show_changed_values", &show_changed_values);
+
+            test_with_context(&context).unwrap()
+        }
+
+        #[test]
+        fn update() {
+            let expected = r#"  # terraform_data.foo-bar will be updated in-place
+  ~ resource "terraform_data" "foo-bar" {
+~     input  = "foo" -> "bar"
+~     output = "foo" -> (known after apply)
+      # (1 unchanged attribute hidden)
+    }"#;
+            pretty_assertions::assert_eq!(test(&tf::tests::PlanType::Update, false), expected);
+
+            let expected = r#"  # terraform_data.foo-bar will be updated in-place
+  ~ resource "terraform_data" "foo-bar" {
+      id     = "72285066-beaf-bd58-0c9f-0c5e7ae166a2"
+~     input  = "foo" -> "bar"
+~     output = "foo" -> (known after apply)
+    }"#;
+            pretty_assertions::assert_eq!(test(&tf::tests::PlanType::Update, true), expected);
+        }
+
+        #[test]
+        fn create() {
+            let expected = r#"  # terraform_data.foo-bar will be created
+  + resource "terraform_data" "foo-bar" {
++     id     = (known after apply)
++     input  = "foo"
++     output = (known after apply)
+    }"#;
+            pretty_assertions::assert_eq!(test(&tf::tests::PlanType::Create, false), expected);
+        }
+
+        #[test]
+        fn delete_create() {
+            let expected = r#"  # random_bytes.test must be replaced
+-/+ resource "random_bytes" "test" {
+~     base64 = (sensitive value) -> (known after apply)
+~     hex    = (sensitive value) -> (known after apply)
+~     length = 4 -> 8 # forces replacement
+    }"#;
+            pretty_assertions::assert_eq!(test(&tf::tests::PlanType::Sensitive, false), expected);
+        }
+
+        #[test]
+        fn not_in_args() {
+            let context = tera::Context::new();
+            let mut tera = tera::Tera::default();
+            tera.register_function("render_terraform", tera_render_terraform(Arc::default()));
+            tera.add_raw_template("template", "{{ render_terraform() }}")
+                .unwrap();
+
+            tera.render("template", &context).unwrap_err();
+        }
+
+        #[test]
+        fn invalid_change() {
+            let mut context = tera::Context::new();
+            context.insert("change", &"invalid".to_string());
+            context.insert("show_changed_values", &false);
+
+            test_with_context(&context).unwrap_err();
+        }
+    }
+
     mod render {
         use super::*;
         use crate::utils;
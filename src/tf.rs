@@ -1,19 +1,69 @@
-use crate::types;
+use crate::{schema, types};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Debug, PartialEq, Clone)]
 #[serde(untagged)]
 pub enum RawValue {
     String(String),
     Integer(i64),
     Float(f64),
+    /// Numbers that cannot be represented exactly by `Integer` or `Float`, keeps the original text
+    #[serde(serialize_with = "serialize_number")]
+    Number(String),
     Boolean(bool),
     Array(Vec<RawValue>),
     Object(RawValueMap),
     Null,
 }
 
+fn serialize_number<S: serde::Serializer>(value: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    match serde_json::Number::from_str(value) {
+        Ok(number) => number.serialize(serializer),
+        Err(_) => serializer.serialize_str(value),
+    }
+}
+
+impl RawValue {
+    fn from_number(number: &serde_json::Number) -> Self {
+        let text = number.to_string();
+        if let Ok(value) = text.parse::<i64>() {
+            if value.to_string() == text {
+                return RawValue::Integer(value);
+            }
+        }
+        if let Ok(value) = text.parse::<f64>() {
+            if serde_json::Number::from_f64(value).is_some_and(|n| n.to_string() == text) {
+                return RawValue::Float(value);
+            }
+        }
+        RawValue::Number(text)
+    }
+
+    fn from_json(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::String(value) => RawValue::String(value),
+            serde_json::Value::Number(number) => RawValue::from_number(&number),
+            serde_json::Value::Bool(value) => RawValue::Boolean(value),
+            serde_json::Value::Array(values) => {
+                RawValue::Array(values.into_iter().map(RawValue::from_json).collect())
+            }
+            serde_json::Value::Object(map) => RawValue::Object(
+                map.into_iter()
+                    .map(|(key, value)| (key, RawValue::from_json(value)))
+                    .collect(),
+            ),
+            serde_json::Value::Null => RawValue::Null,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RawValue {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde_json::Value::deserialize(deserializer).map(RawValue::from_json)
+    }
+}
+
 pub type RawValueMap = std::collections::HashMap<String, RawValue>;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -22,6 +72,7 @@ pub enum Value {
     String(String),
     Integer(i64),
     Float(f64),
+    Number(String),
     Boolean(bool),
     Array(Vec<Value>),
     Object(ValueMap),
@@ -36,6 +87,7 @@ impl Value {
             RawValue::String(value) => Value::String(value.clone()),
             RawValue::Integer(value) => Value::Integer(*value),
             RawValue::Float(value) => Value::Float(*value),
+            RawValue::Number(value) => Value::Number(value.clone()),
             RawValue::Boolean(value) => Value::Boolean(*value),
             RawValue::Array(values) => {
                 let mut new_values = Vec::new();
@@ -54,10 +106,160 @@ impl Value {
             RawValue::Null => Value::Null,
         }
     }
+
+    /// Checks whether the value is null, an empty string, an empty array or an empty object
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Value::Null => true,
+            Value::String(value) => value.is_empty(),
+            Value::Array(values) => values.is_empty(),
+            Value::Object(map) => map.is_empty(),
+            _ => false,
+        }
+    }
+
+    /// Decodes a JSON-encoded string, only objects and arrays are considered
+    #[must_use]
+    pub fn from_json_string(value: &str) -> Option<Self> {
+        match serde_json::from_str::<RawValue>(value) {
+            Ok(raw @ (RawValue::Object(_) | RawValue::Array(_))) => Some(Value::from_raw(&raw)),
+            _ => None,
+        }
+    }
 }
 
 pub type ValueMap = std::collections::HashMap<String, Value>;
 
+#[derive(Debug, PartialEq, Clone)]
+pub enum ArrayDiffOp {
+    Unchanged { before: usize, after: usize },
+    Changed { before: usize, after: usize },
+    Removed { before: usize },
+    Added { after: usize },
+}
+
+fn push_array_diff_hunk(result: &mut Vec<ArrayDiffOp>, removed: &[usize], added: &[usize]) {
+    for (before, after) in removed.iter().zip(added.iter()) {
+        result.push(ArrayDiffOp::Changed {
+            before: *before,
+            after: *after,
+        });
+    }
+    for before in removed.iter().skip(added.len()) {
+        result.push(ArrayDiffOp::Removed { before: *before });
+    }
+    for after in added.iter().skip(removed.len()) {
+        result.push(ArrayDiffOp::Added { after: *after });
+    }
+}
+
+/// Size of the longest common subsequence table above which arrays are diffed by position
+const MAX_ARRAY_DIFF_CELLS: usize = 1_000_000;
+
+/// Matches array elements by their position, used for arrays too large for `diff_arrays`
+fn diff_arrays_by_position(before: &[Value], after: &[Value]) -> Vec<ArrayDiffOp> {
+    let mut result = Vec::new();
+    for index in 0..before.len().max(after.len()) {
+        result.push(match (before.get(index), after.get(index)) {
+            (Some(before_value), Some(after_value)) if before_value == after_value => {
+                ArrayDiffOp::Unchanged {
+                    before: index,
+                    after: index,
+                }
+            }
+            (Some(_), Some(_)) => ArrayDiffOp::Changed {
+                before: index,
+                after: index,
+            },
+            (Some(_), None) => ArrayDiffOp::Removed { before: index },
+            _ => ArrayDiffOp::Added { after: index },
+        });
+    }
+    result
+}
+
+/// Matches array elements by their longest common subsequence.
+/// Removals and additions between two matches are paired as changes.
+/// Arrays exceeding `MAX_ARRAY_DIFF_CELLS` are matched by position.
+#[must_use]
+pub fn diff_arrays(before: &[Value], after: &[Value]) -> Vec<ArrayDiffOp> {
+    if (before.len() + 1).saturating_mul(after.len() + 1) > MAX_ARRAY_DIFF_CELLS {
+        return diff_arrays_by_position(before, after);
+    }
+
+    let mut lengths = vec![vec![0_usize; after.len() + 1]; before.len() + 1];
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            lengths[i][j] = if before[i] == after[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::new();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() || j < after.len() {
+        if i < before.len() && j < after.len() && before[i] == after[j] {
+            push_array_diff_hunk(&mut result, &removed, &added);
+            removed.clear();
+            added.clear();
+            result.push(ArrayDiffOp::Unchanged {
+                before: i,
+                after: j,
+            });
+            i += 1;
+            j += 1;
+        } else if j < after.len() && (i == before.len() || lengths[i][j + 1] >= lengths[i + 1][j]) {
+            added.push(j);
+            j += 1;
+        } else {
+            removed.push(i);
+            i += 1;
+        }
+    }
+    push_array_diff_hunk(&mut result, &removed, &added);
+    result
+}
+
+/// Matches set elements regardless of their order.
+/// Elements without an equal counterpart are reported as removed or added.
+#[must_use]
+pub fn diff_sets(before: &[Value], after: &[Value]) -> Vec<ArrayDiffOp> {
+    let mut matched = vec![false; before.len()];
+    let mut result = Vec::new();
+    let mut added = Vec::new();
+    for (after_index, value) in after.iter().enumerate() {
+        let before_index = before
+            .iter()
+            .enumerate()
+            .position(|(index, before)| !matched[index] && before == value);
+        match before_index {
+            Some(before_index) => {
+                matched[before_index] = true;
+                result.push(ArrayDiffOp::Unchanged {
+                    before: before_index,
+                    after: after_index,
+                });
+            }
+            None => added.push(ArrayDiffOp::Added { after: after_index }),
+        }
+    }
+    for (before_index, matched) in matched.into_iter().enumerate() {
+        if !matched {
+            result.push(ArrayDiffOp::Removed {
+                before: before_index,
+            });
+        }
+    }
+    result.extend(added);
+    result
+}
+
 fn value_map_from_raw(raw_map: &RawValueMap) -> ValueMap {
     let mut new_map = ValueMap::new();
     for (key, value) in raw_map {
@@ -109,6 +311,30 @@ pub enum Action {
 }
 
 impl Action {
+    pub const ALL: [Action; 7] = [
+        Action::Create,
+        Action::DeleteCreate,
+        Action::Read,
+        Action::Update,
+        Action::Delete,
+        Action::NoOp,
+        Action::Unknown,
+    ];
+
+    /// Action name as used by Terraform, `replace` stands for delete and create
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Action::Create => "create",
+            Action::DeleteCreate => "replace",
+            Action::Read => "read",
+            Action::Update => "update",
+            Action::Delete => "delete",
+            Action::NoOp => "no-op",
+            Action::Unknown => "unknown",
+        }
+    }
+
     #[must_use]
     pub fn from_actions(actions: &[RawResourceChangeChangeAction]) -> Action {
         if actions.len() == 2
@@ -116,7 +342,7 @@ impl Action {
             && actions.contains(&RawResourceChangeChangeAction::Delete)
         {
             return Action::DeleteCreate;
-        };
+        }
         if actions.len() == 1 {
             return match actions[0] {
                 RawResourceChangeChangeAction::Create => Action::Create,
@@ -147,22 +373,56 @@ impl Action {
     }
 }
 
+impl FromStr for Action {
+    type Err = types::Error;
+
+    /// Parses action names as returned by `as_str`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.as_str() == s)
+            .ok_or_else(|| types::Error::default(format!("Invalid action: {s}")))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum RawPathStep {
+    Key(String),
+    Index(usize),
+}
+
+impl RawPathStep {
+    #[must_use]
+    pub fn to_path_string(&self) -> String {
+        match self {
+            RawPathStep::Key(key) => key.clone(),
+            RawPathStep::Index(index) => index.to_string(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct RawResourceChangeChange {
     pub actions: Vec<RawResourceChangeChangeAction>,
     pub before: Option<RawValueMap>,
     pub after: Option<RawValueMap>,
-    // after_unknown
+    #[serde(default)]
+    pub after_unknown: Option<BoolValue>,
     pub before_sensitive: Option<BoolValue>,
     pub after_sensitive: Option<BoolValue>,
+    #[serde(default)]
+    pub replace_paths: Option<Vec<Vec<RawPathStep>>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct RawResourceChange {
     pub address: String,
-    // mode: String,
-    // #[serde(rename = "type")]
-    // type_: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module_address: Option<String>,
+    pub mode: String,
+    #[serde(rename = "type")]
+    pub type_: String,
     pub name: String,
     // provider_name: String,
     pub change: RawResourceChangeChange,
@@ -201,13 +461,461 @@ impl RawPlan {
     }
 }
 
-#[derive(Serialize, Debug, PartialEq)]
+/// Checks whether the path is equal to or nested in the pattern, `*` matches any single step
+#[must_use]
+pub fn path_matches(pattern: &[String], path: &[String]) -> bool {
+    pattern.len() <= path.len()
+        && pattern
+            .iter()
+            .zip(path)
+            .all(|(pattern_step, path_step)| pattern_step == "*" || pattern_step == path_step)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct IgnoreRule {
+    pub resource_type: Option<String>,
+    pub path: Vec<String>,
+}
+
+impl FromStr for IgnoreRule {
+    type Err = types::Error;
+
+    /// Parses `path.to.attribute` or `resource_type:path.to.attribute`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (resource_type, path) = match s.split_once(':') {
+            Some((resource_type, path)) => (Some(resource_type.to_string()), path),
+            None => (None, s),
+        };
+        let path: Vec<String> = path.split('.').map(ToString::to_string).collect();
+        if resource_type.as_deref() == Some("") || path.iter().any(String::is_empty) {
+            return Err(types::Error::default(format!("Invalid ignore rule: {s}")));
+        }
+        Ok(IgnoreRule {
+            resource_type,
+            path,
+        })
+    }
+}
+
+impl IgnoreRule {
+    #[must_use]
+    pub fn applies_to(&self, resource_type: &str) -> bool {
+        self.resource_type
+            .as_ref()
+            .is_none_or(|rule_type| rule_type == resource_type)
+    }
+}
+
+/// Selects the resource changes to be kept in plans
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Filter {
+    pub exclude_actions: Vec<Action>,
+    /// Changes are kept if the address matches any of the patterns, all are kept if empty
+    pub include_addresses: Vec<glob::Pattern>,
+    pub exclude_types: Vec<glob::Pattern>,
+    pub exclude_modes: Vec<String>,
+}
+
+impl Filter {
+    #[must_use]
+    pub fn includes(&self, raw: &RawResourceChange) -> bool {
+        let action = Action::from_actions(&raw.change.actions);
+        !self.exclude_actions.contains(&action)
+            && (self.include_addresses.is_empty()
+                || self
+                    .include_addresses
+                    .iter()
+                    .any(|pattern| pattern.matches(&raw.address)))
+            && !self
+                .exclude_types
+                .iter()
+                .any(|pattern| pattern.matches(&raw.type_))
+            && !self.exclude_modes.contains(&raw.mode)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Options {
+    pub ignore_rules: Vec<IgnoreRule>,
+    /// Treat null, empty strings, empty arrays and empty objects as equivalent
+    pub normalize_empty: bool,
+    pub schemas: schema::Registry,
+    pub filter: Filter,
+    /// Build the module tree of each plan
+    pub group_by_module: bool,
+    /// Group instances of resources with `count` or `for_each`
+    pub group_instances: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Changed,
+    Unchanged,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct DiffNode {
+    pub path: Vec<String>,
+    pub kind: DiffKind,
+    /// Only set for leaf values, nested objects and arrays are described by children
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub sensitive: bool,
+    pub unknown: bool,
+    pub forces_replacement: bool,
+    pub ignored: bool,
+    /// Set for differences between empty values when empty values are normalized
+    pub cosmetic: bool,
+    /// Attribute description from the provider schema
+    pub description: Option<String>,
+    pub children: Vec<DiffNode>,
+}
+
+impl DiffNode {
+    /// Checks whether the node has differences, ignoring the ignored paths
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.kind != DiffKind::Unchanged
+            && !self.ignored
+            && !self.cosmetic
+            && (self.children.is_empty() || self.children.iter().any(DiffNode::is_significant))
+    }
+}
+
+/// Nested markers of a resource change, e.g. `after_unknown` and `before_sensitive`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Markers {
+    pub unknown: BoolValue,
+    pub before_sensitive: BoolValue,
+    pub after_sensitive: BoolValue,
+}
+
+impl Default for Markers {
+    fn default() -> Self {
+        Markers {
+            unknown: BoolValue::Boolean(false),
+            before_sensitive: BoolValue::Boolean(false),
+            after_sensitive: BoolValue::Boolean(false),
+        }
+    }
+}
+
+impl Markers {
+    fn child(&self, key: &str) -> Self {
+        Markers {
+            unknown: get_child_unknown(&self.unknown, key),
+            before_sensitive: get_child_sensitive(&self.before_sensitive, key),
+            after_sensitive: get_child_sensitive(&self.after_sensitive, key),
+        }
+    }
+
+    /// Markers of an array element, `before` and `after` are the element indexes on each side
+    fn element(&self, before: Option<usize>, after: Option<usize>) -> Self {
+        Markers {
+            unknown: after.map_or(BoolValue::Boolean(false), |index| {
+                get_element(&self.unknown, index)
+            }),
+            before_sensitive: before.map_or(BoolValue::Boolean(false), |index| {
+                get_element(&self.before_sensitive, index)
+            }),
+            after_sensitive: after.map_or(BoolValue::Boolean(false), |index| {
+                get_element(&self.after_sensitive, index)
+            }),
+        }
+    }
+}
+
+struct DiffContext<'a> {
+    replace_paths: &'a [Vec<String>],
+    ignored_paths: &'a [Vec<String>],
+    normalize_empty: bool,
+    schema: Option<&'a schema::SchemaNode>,
+}
+
+fn get_child_unknown(unknown: &BoolValue, key: &str) -> BoolValue {
+    match unknown {
+        BoolValue::Object(map) => map.get(key).cloned().unwrap_or(BoolValue::Boolean(false)),
+        _ => BoolValue::Boolean(false),
+    }
+}
+
+fn get_element(value: &BoolValue, index: usize) -> BoolValue {
+    match value {
+        BoolValue::Array(array) => array
+            .get(index)
+            .cloned()
+            .unwrap_or(BoolValue::Boolean(false)),
+        _ => BoolValue::Boolean(false),
+    }
+}
+
+fn non_null(value: Option<&Value>) -> Option<&Value> {
+    value.filter(|value| **value != Value::Null)
+}
+
+fn child_path(path: &[String], step: String) -> Vec<String> {
+    let mut child = path.to_vec();
+    child.push(step);
+    child
+}
+
+fn diff_kind(before: Option<&Value>, after: Option<&Value>, children: &[DiffNode]) -> DiffKind {
+    match (before, after) {
+        (None, None) => DiffKind::Unchanged,
+        (None, Some(_)) => DiffKind::Added,
+        (Some(_), None) => DiffKind::Removed,
+        (Some(_), Some(_)) => {
+            if children
+                .iter()
+                .all(|child| child.kind == DiffKind::Unchanged)
+            {
+                DiffKind::Unchanged
+            } else {
+                DiffKind::Changed
+            }
+        }
+    }
+}
+
+fn diff_map(
+    context: &DiffContext,
+    path: &[String],
+    before: Option<&ValueMap>,
+    after: Option<&ValueMap>,
+    markers: &Markers,
+) -> Vec<DiffNode> {
+    let mut keys: Vec<&String> = Vec::new();
+    keys.extend(before.iter().flat_map(|map| map.keys()));
+    keys.extend(after.iter().flat_map(|map| map.keys()));
+    if let BoolValue::Object(unknown_map) = &markers.unknown {
+        keys.extend(unknown_map.keys());
+    }
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .map(|key| {
+            diff_value(
+                context,
+                child_path(path, key.clone()),
+                before.and_then(|map| map.get(key)),
+                after.and_then(|map| map.get(key)),
+                &markers.child(key),
+            )
+        })
+        .collect()
+}
+
+fn diff_array(
+    context: &DiffContext,
+    path: &[String],
+    before: &[Value],
+    after: &[Value],
+    markers: &Markers,
+) -> Vec<DiffNode> {
+    let is_set = context
+        .schema
+        .and_then(|schema| schema.find(path))
+        .is_some_and(schema::SchemaNode::is_set);
+    let ops = if is_set {
+        diff_sets(before, after)
+    } else {
+        diff_arrays(before, after)
+    };
+    ops.into_iter()
+        .map(|op| match op {
+            ArrayDiffOp::Unchanged {
+                before: before_index,
+                after: after_index,
+            }
+            | ArrayDiffOp::Changed {
+                before: before_index,
+                after: after_index,
+            } => diff_value(
+                context,
+                child_path(path, after_index.to_string()),
+                before.get(before_index),
+                after.get(after_index),
+                &markers.element(Some(before_index), Some(after_index)),
+            ),
+            ArrayDiffOp::Removed { before: index } => diff_value(
+                context,
+                child_path(path, index.to_string()),
+                before.get(index),
+                None,
+                &markers.element(Some(index), None),
+            ),
+            ArrayDiffOp::Added { after: index } => diff_value(
+                context,
+                child_path(path, index.to_string()),
+                None,
+                after.get(index),
+                &markers.element(None, Some(index)),
+            ),
+        })
+        .collect()
+}
+
+/// Objects marked as sensitive are masked key by key, other values are masked as a whole
+fn is_masked(value: Option<&Value>, sensitive: &BoolValue, schema_sensitive: bool) -> bool {
+    value.is_some_and(|value| {
+        *value == Value::Sensitive
+            || schema_sensitive
+            || (is_sensitive(sensitive) && !matches!(value, Value::Object(_)))
+    })
+}
+
+fn masked(value: Option<&Value>, is_masked: bool) -> Option<Value> {
+    if is_masked {
+        Some(Value::Sensitive)
+    } else {
+        value.cloned()
+    }
+}
+
+fn diff_value(
+    context: &DiffContext,
+    path: Vec<String>,
+    before: Option<&Value>,
+    after: Option<&Value>,
+    markers: &Markers,
+) -> DiffNode {
+    let before = non_null(before);
+    let after = non_null(after);
+    let forces_replacement = context.replace_paths.contains(&path);
+    let ignored = context
+        .ignored_paths
+        .iter()
+        .any(|pattern| path_matches(pattern, &path));
+    let schema = context.schema.and_then(|schema| schema.find(&path));
+    let description = schema.and_then(|schema| schema.description.clone());
+    // Values are compared unmasked, only the values kept in the node are masked
+    let schema_sensitive = schema.is_some_and(|schema| schema.sensitive);
+    let before_masked = is_masked(before, &markers.before_sensitive, schema_sensitive);
+    let after_masked = is_masked(after, &markers.after_sensitive, schema_sensitive);
+    let sensitive = before_masked || after_masked;
+
+    if is_sensitive(&markers.unknown) {
+        return DiffNode {
+            path,
+            kind: if before.is_some() {
+                DiffKind::Changed
+            } else {
+                DiffKind::Added
+            },
+            before: masked(before, before_masked),
+            after: None,
+            sensitive,
+            unknown: true,
+            forces_replacement,
+            ignored,
+            cosmetic: false,
+            description,
+            children: Vec::new(),
+        };
+    }
+
+    let children = match (before, after) {
+        _ if sensitive => None,
+        (Some(Value::Object(before)), Some(Value::Object(after))) => {
+            Some(diff_map(context, &path, Some(before), Some(after), markers))
+        }
+        (None, Some(Value::Object(after))) => {
+            Some(diff_map(context, &path, None, Some(after), markers))
+        }
+        (Some(Value::Object(before)), None) => {
+            Some(diff_map(context, &path, Some(before), None, markers))
+        }
+        (Some(Value::Array(before)), Some(Value::Array(after))) => {
+            Some(diff_array(context, &path, before, after, markers))
+        }
+        (None, Some(Value::Array(after))) => Some(diff_array(context, &path, &[], after, markers)),
+        (Some(Value::Array(before)), None) => {
+            Some(diff_array(context, &path, before, &[], markers))
+        }
+        _ => None,
+    };
+
+    match children {
+        Some(children) if !children.is_empty() => DiffNode {
+            path,
+            kind: diff_kind(before, after, &children),
+            before: None,
+            after: None,
+            sensitive,
+            unknown: false,
+            forces_replacement,
+            ignored,
+            cosmetic: false,
+            description,
+            children,
+        },
+        _ => {
+            let kind = match (before, after) {
+                _ if before == after => DiffKind::Unchanged,
+                (Some(_), Some(_)) => DiffKind::Changed,
+                _ => diff_kind(before, after, &[]),
+            };
+            let cosmetic = context.normalize_empty
+                && kind != DiffKind::Unchanged
+                && before.is_none_or(Value::is_empty)
+                && after.is_none_or(Value::is_empty);
+            DiffNode {
+                path,
+                kind,
+                before: masked(before, before_masked),
+                after: masked(after, after_masked),
+                sensitive,
+                unknown: false,
+                forces_replacement,
+                ignored,
+                cosmetic,
+                description,
+                children: Vec::new(),
+            }
+        }
+    }
+}
+
+/// Computes the diff tree of resource attributes from unmasked values,
+/// sensitive values are compared but masked in the nodes
+#[must_use]
+pub fn diff_values(
+    before: Option<&ValueMap>,
+    after: Option<&ValueMap>,
+    markers: &Markers,
+    replace_paths: &[Vec<String>],
+    ignored_paths: &[Vec<String>],
+    normalize_empty: bool,
+    schema: Option<&schema::SchemaNode>,
+) -> Vec<DiffNode> {
+    let context = DiffContext {
+        replace_paths,
+        ignored_paths,
+        normalize_empty,
+        schema,
+    };
+    diff_map(&context, &[], before, after, markers)
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct Change {
     pub address: String,
+    pub mode: String,
+    #[serde(rename = "type")]
+    pub type_: String,
     pub name: String,
     pub action: Action,
     pub before: Option<ValueMap>,
     pub after: Option<ValueMap>,
+    pub diff: Vec<DiffNode>,
+    pub ignored_paths: Vec<Vec<String>>,
+    /// Set for updates where all differences are in ignored paths or between empty values
+    pub cosmetic: bool,
     pub raw: RawResourceChange,
 }
 
@@ -261,9 +969,38 @@ fn mask_sensitive_map(mut value_map: ValueMap, sensitive: &BoolValue) -> ValueMa
     value_map
 }
 
+fn mask_schema_sensitive(value: Value, schema: &schema::SchemaNode) -> Value {
+    if schema.sensitive && value != Value::Null {
+        return Value::Sensitive;
+    }
+    match value {
+        Value::Object(value_map) => Value::Object(mask_schema_sensitive_map(value_map, schema)),
+        Value::Array(value_array) => Value::Array(
+            value_array
+                .into_iter()
+                .enumerate()
+                .map(|(index, value)| match schema.child(&index.to_string()) {
+                    Some(child) => mask_schema_sensitive(value, child),
+                    None => value,
+                })
+                .collect(),
+        ),
+        _ => value,
+    }
+}
+
+fn mask_schema_sensitive_map(mut value_map: ValueMap, schema: &schema::SchemaNode) -> ValueMap {
+    for (key, value) in &mut value_map {
+        if let Some(child) = schema.child(key) {
+            *value = mask_schema_sensitive(value.clone(), child);
+        }
+    }
+    value_map
+}
+
 impl Change {
     #[must_use]
-    pub fn from_raw(raw: RawResourceChange) -> Self {
+    pub fn from_raw(raw: RawResourceChange, options: &Options) -> Self {
         let before_sensitive = raw
             .change
             .before_sensitive
@@ -275,39 +1012,348 @@ impl Change {
             .as_ref()
             .unwrap_or(&BoolValue::Boolean(false));
 
+        let schema = options.schemas.get(&raw.mode, &raw.type_);
+        let mask = |value_map: ValueMap, sensitive: &BoolValue| {
+            let value_map = mask_sensitive_map(value_map, sensitive);
+            match schema {
+                Some(schema) => mask_schema_sensitive_map(value_map, schema),
+                None => value_map,
+            }
+        };
+
         let raw_before = raw.change.before.as_ref();
         let unmasked_before = raw_before.map(value_map_from_raw);
-        let before = unmasked_before.map(|before| mask_sensitive_map(before, before_sensitive));
+        let before = unmasked_before
+            .clone()
+            .map(|before| mask(before, before_sensitive));
 
         let raw_after = raw.change.after.as_ref();
         let unmasked_after = raw_after.map(value_map_from_raw);
-        let after = unmasked_after.map(|after| mask_sensitive_map(after, after_sensitive));
+        let after = unmasked_after
+            .clone()
+            .map(|after| mask(after, after_sensitive));
+
+        let replace_paths: Vec<Vec<String>> = raw
+            .change
+            .replace_paths
+            .iter()
+            .flatten()
+            .map(|path| path.iter().map(RawPathStep::to_path_string).collect())
+            .collect();
+        let ignored_paths: Vec<Vec<String>> = options
+            .ignore_rules
+            .iter()
+            .filter(|rule| rule.applies_to(&raw.type_))
+            .map(|rule| rule.path.clone())
+            .collect();
+        let markers = Markers {
+            unknown: raw
+                .change
+                .after_unknown
+                .clone()
+                .unwrap_or(BoolValue::Boolean(false)),
+            before_sensitive: before_sensitive.clone(),
+            after_sensitive: after_sensitive.clone(),
+        };
+        let diff = diff_values(
+            unmasked_before.as_ref(),
+            unmasked_after.as_ref(),
+            &markers,
+            &replace_paths,
+            &ignored_paths,
+            options.normalize_empty,
+            schema,
+        );
+        let action = Action::from_actions(&raw.change.actions);
+        let cosmetic = action == Action::Update
+            && diff.iter().any(|node| node.kind != DiffKind::Unchanged)
+            && !diff.iter().any(DiffNode::is_significant);
 
         Change {
             address: raw.address.clone(),
+            mode: raw.mode.clone(),
+            type_: raw.type_.clone(),
             name: raw.name.clone(),
-            action: Action::from_actions(&raw.change.actions),
+            action,
             before,
             after,
+            diff,
+            ignored_paths,
+            cosmetic,
             raw,
         }
     }
 }
 
+/// Number of changes per action, `add`, `change` and `destroy` are counted as in the Terraform plan summary
+#[derive(Serialize, Debug, Default, PartialEq, Clone)]
+pub struct ActionCounts {
+    pub create: usize,
+    pub update: usize,
+    pub delete_create: usize,
+    pub delete: usize,
+    pub read: usize,
+    pub no_op: usize,
+    pub unknown: usize,
+    pub add: usize,
+    pub change: usize,
+    pub destroy: usize,
+}
+
+impl ActionCounts {
+    #[must_use]
+    pub fn from_actions<'a>(actions: impl IntoIterator<Item = &'a Action>) -> Self {
+        let mut counts = ActionCounts::default();
+        for action in actions {
+            counts.count(action);
+        }
+        counts
+    }
+
+    pub fn count(&mut self, action: &Action) {
+        match action {
+            Action::Create => {
+                self.create += 1;
+                self.add += 1;
+            }
+            Action::Update => {
+                self.update += 1;
+                self.change += 1;
+            }
+            Action::DeleteCreate => {
+                self.delete_create += 1;
+                self.add += 1;
+                self.destroy += 1;
+            }
+            Action::Delete => {
+                self.delete += 1;
+                self.destroy += 1;
+            }
+            Action::Read => self.read += 1,
+            Action::NoOp => self.no_op += 1,
+            Action::Unknown => self.unknown += 1,
+        }
+    }
+}
+
+/// Splits an address into steps separated by dots outside of brackets and quotes
+fn split_address(address: &str) -> Vec<String> {
+    let mut steps = Vec::new();
+    let mut step = String::new();
+    let mut in_brackets = false;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for c in address.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' if in_brackets => in_quotes = !in_quotes,
+            '[' if !in_quotes => in_brackets = true,
+            ']' if !in_quotes => in_brackets = false,
+            '.' if !in_brackets => {
+                steps.push(std::mem::take(&mut step));
+                continue;
+            }
+            _ => {}
+        }
+        step.push(c);
+    }
+    steps.push(step);
+    steps
+}
+
+/// Returns module calls of the address, e.g. `["module.app", "module.db[0]"]`
+/// for `module.app.module.db[0]` or `module.app.module.db[0].aws_s3_bucket.this`
+#[must_use]
+pub fn module_path(address: &str) -> Vec<String> {
+    let steps = split_address(address);
+    let mut path = Vec::new();
+    let mut index = 0;
+    while index + 1 < steps.len() && steps[index] == "module" {
+        path.push(format!("module.{}", steps[index + 1]));
+        index += 2;
+    }
+    path
+}
+
+/// Splits an address into the resource address and the instance key, e.g. `["a"]` or `[0]`,
+/// the key is empty for resources without `count` or `for_each`
+#[must_use]
+pub fn split_instance_key(address: &str) -> (String, String) {
+    let steps = split_address(address);
+    let last = steps.last().map_or("", String::as_str);
+    match last.find('[') {
+        Some(position) => {
+            let key_length = last.len() - position;
+            (
+                address[..address.len() - key_length].to_string(),
+                last[position..].to_string(),
+            )
+        }
+        None => (address.to_string(), String::new()),
+    }
+}
+
+fn significant_diff(diff: &[DiffNode]) -> Vec<DiffNode> {
+    diff.iter()
+        .filter(|node| node.is_significant())
+        .map(|node| DiffNode {
+            children: significant_diff(&node.children),
+            ..node.clone()
+        })
+        .collect()
+}
+
+/// Instances of a resource created with `count` or `for_each`
+#[derive(Serialize, Debug, PartialEq, Default)]
+pub struct InstanceGroup {
+    /// Resource address without the instance key
+    pub address: String,
+    pub keys: Vec<String>,
+    pub unique_actions: Vec<Action>,
+    /// Indexes of the instance changes in the plan changes
+    pub changes: Vec<usize>,
+    /// Set when all instances have the same action and differences
+    pub identical: bool,
+}
+
+impl InstanceGroup {
+    /// Groups changes by the resource address, cosmetic changes are not grouped
+    #[must_use]
+    pub fn from_changes(changes: &[Change]) -> Vec<Self> {
+        let mut groups: Vec<InstanceGroup> = Vec::new();
+        for (index, change) in changes.iter().enumerate() {
+            if change.cosmetic {
+                continue;
+            }
+            let (address, key) = split_instance_key(&change.address);
+            let position =
+                if let Some(position) = groups.iter().position(|group| group.address == address) {
+                    position
+                } else {
+                    groups.push(InstanceGroup {
+                        address,
+                        ..InstanceGroup::default()
+                    });
+                    groups.len() - 1
+                };
+            let group = &mut groups[position];
+            group.keys.push(key);
+            group.changes.push(index);
+            if !group.unique_actions.contains(&change.action) {
+                group.unique_actions.push(change.action.clone());
+                group.unique_actions.sort();
+            }
+        }
+        for group in &mut groups {
+            let first = &changes[group.changes[0]];
+            let diff = significant_diff(&first.diff);
+            group.identical = group.unique_actions.len() == 1
+                && group.changes[1..]
+                    .iter()
+                    .all(|index| significant_diff(&changes[*index].diff) == diff);
+        }
+        groups
+    }
+}
+
+/// Module calls of the change, the module address of the plan is preferred over the address
+#[must_use]
+pub fn change_module_path(change: &Change) -> Vec<String> {
+    match &change.raw.module_address {
+        Some(module_address) => module_path(module_address),
+        None => module_path(&change.address),
+    }
+}
+
+/// Changes of a plan grouped by the module they belong to
+#[derive(Serialize, Debug, PartialEq, Default)]
+pub struct Module {
+    /// Full module address, empty for the root module
+    pub address: String,
+    /// Actions of the changes in the module and its nested modules
+    pub unique_actions: Vec<Action>,
+    /// Indexes of the module changes in the plan changes
+    pub changes: Vec<usize>,
+    /// Indexes of the module instance groups in the plan groups, only set when instances are grouped
+    pub groups: Vec<usize>,
+    pub modules: Vec<Module>,
+}
+
+impl Module {
+    /// Builds the module tree, cosmetic changes are not part of any module
+    #[must_use]
+    pub fn from_changes(changes: &[Change], groups: Option<&[InstanceGroup]>) -> Self {
+        let mut root = Module::default();
+        for (index, change) in changes.iter().enumerate() {
+            if change.cosmetic {
+                continue;
+            }
+            root.module_mut(&change_module_path(change), &change.action)
+                .changes
+                .push(index);
+        }
+        for (index, group) in groups.unwrap_or_default().iter().enumerate() {
+            let change = &changes[group.changes[0]];
+            root.module_mut(&change_module_path(change), &change.action)
+                .groups
+                .push(index);
+        }
+        root
+    }
+
+    fn module_mut(&mut self, path: &[String], action: &Action) -> &mut Module {
+        if !self.unique_actions.contains(action) {
+            self.unique_actions.push(action.clone());
+            self.unique_actions.sort();
+        }
+        let Some((step, rest)) = path.split_first() else {
+            return self;
+        };
+        let address = if self.address.is_empty() {
+            step.clone()
+        } else {
+            format!("{}.{step}", self.address)
+        };
+        let position = if let Some(position) = self
+            .modules
+            .iter()
+            .position(|module| module.address == address)
+        {
+            position
+        } else {
+            self.modules.push(Module {
+                address,
+                ..Module::default()
+            });
+            self.modules.len() - 1
+        };
+        self.modules[position].module_mut(rest, action)
+    }
+}
+
 #[derive(Serialize, Debug, PartialEq)]
 pub struct Plan {
     changes: Vec<Change>,
     unique_actions: Vec<Action>,
+    counts: ActionCounts,
+    /// Only built when changes are grouped by module
+    modules: Option<Module>,
+    /// Only built when instances are grouped
+    groups: Option<Vec<InstanceGroup>>,
     raw: RawPlan,
 }
 
 impl Plan {
     #[must_use]
-    pub fn from_raw(raw: RawPlan) -> Self {
+    pub fn from_raw(raw: RawPlan, options: &Options) -> Self {
         let mut changes: Vec<Change> = Vec::new();
         if let Some(resource_changes) = &raw.resource_changes {
             for raw_change in resource_changes {
-                changes.push(Change::from_raw(raw_change.clone()));
+                if !options.filter.includes(raw_change) {
+                    continue;
+                }
+                changes.push(Change::from_raw(raw_change.clone(), options));
             }
         }
         let mut unique_actions: Vec<Action> = Vec::new();
@@ -317,24 +1363,72 @@ impl Plan {
             }
         }
         unique_actions.sort();
+        let counts = ActionCounts::from_actions(changes.iter().map(|change| &change.action));
+        let groups = options
+            .group_instances
+            .then(|| InstanceGroup::from_changes(&changes));
+        let modules = options
+            .group_by_module
+            .then(|| Module::from_changes(&changes, groups.as_deref()));
 
         Plan {
             changes,
             unique_actions,
+            counts,
+            modules,
+            groups,
             raw,
         }
     }
+
+    #[must_use]
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    #[must_use]
+    pub fn unique_actions(&self) -> &[Action] {
+        &self.unique_actions
+    }
+
+    #[must_use]
+    pub fn counts(&self) -> &ActionCounts {
+        &self.counts
+    }
+
+    #[must_use]
+    pub fn raw(&self) -> &RawPlan {
+        &self.raw
+    }
 }
 
 #[derive(Serialize, Debug, PartialEq)]
 pub struct Data {
     pub plans: std::collections::HashMap<String, Plan>,
+    pub counts: ActionCounts,
+    /// Provider schemas the plans were read with, renderers look resources up by `mode` and `type`
+    #[serde(skip)]
+    pub schemas: std::sync::Arc<schema::Registry>,
 }
 
 impl Data {
+    #[must_use]
+    pub fn from_plans(plans: std::collections::HashMap<String, Plan>) -> Self {
+        let counts = ActionCounts::from_actions(
+            plans
+                .values()
+                .flat_map(|plan| plan.changes.iter().map(|change| &change.action)),
+        );
+        Data {
+            plans,
+            counts,
+            schemas: std::sync::Arc::default(),
+        }
+    }
+
     /// # Errors
     /// Returns an error if any of the files cannot be read or parsed
-    pub fn from_files(paths: &[String]) -> Result<Self, types::Error> {
+    pub fn from_files(paths: &[String], options: &Options) -> Result<Self, types::Error> {
         let mut plans: std::collections::HashMap<String, Plan> = std::collections::HashMap::new();
         for path_glob in paths {
             let glob = glob::glob(path_glob).map_err(|e| {
@@ -353,7 +1447,7 @@ impl Data {
                 };
                 let plan = RawPlan::from_file(path)
                     .map_err(|e| types::Error::chain(format!("Failed to read file({path})"), e))?;
-                plans.insert(path.to_string(), Plan::from_raw(plan));
+                plans.insert(path.to_string(), Plan::from_raw(plan, options));
 
                 file_count += 1;
             }
@@ -364,7 +1458,10 @@ impl Data {
                 )));
             }
         }
-        Ok(Data { plans })
+        Ok(Data {
+            schemas: std::sync::Arc::new(options.schemas.clone()),
+            ..Data::from_plans(plans)
+        })
     }
 }
 
@@ -410,7 +1507,7 @@ pub mod tests {
             plans.insert(get_test_plan_file(&plan_type), get_test_plan(&plan_type));
         }
 
-        return Data { plans };
+        return Data::from_plans(plans);
     }
 
     pub fn get_test_plan_file(plan_type: &PlanType) -> String {
@@ -431,7 +1528,7 @@ pub mod tests {
     pub fn get_test_plan(plan_type: &PlanType) -> Plan {
         let json = get_test_plan_json(plan_type);
         let raw = RawPlan::from_str(&json).unwrap();
-        return Plan::from_raw(raw);
+        return Plan::from_raw(raw, &Options::default());
     }
 
     pub fn get_test_plan_json(plan_type: &PlanType) -> String {
@@ -559,6 +1656,542 @@ pub mod tests {
         }
     }
 
+    mod raw_value {
+        use super::*;
+
+        fn parse(json: &str) -> RawValue {
+            serde_json::from_str::<RawValue>(json).unwrap()
+        }
+
+        #[test]
+        fn numbers() {
+            assert_eq!(parse("42"), RawValue::Integer(42));
+            assert_eq!(parse("-42"), RawValue::Integer(-42));
+            assert_eq!(parse("42.1"), RawValue::Float(42.1));
+            assert_eq!(parse("1.0"), RawValue::Float(1.0));
+            assert_eq!(
+                parse("123456789012345678901234567890"),
+                RawValue::Number("123456789012345678901234567890".to_string())
+            );
+            assert_eq!(
+                parse("18446744073709551615"),
+                RawValue::Number("18446744073709551615".to_string())
+            );
+            assert_eq!(
+                parse("0.10000000000000000001"),
+                RawValue::Number("0.10000000000000000001".to_string())
+            );
+            assert_eq!(parse("1e3"), RawValue::Number("1e3".to_string()));
+        }
+
+        #[test]
+        fn nested() {
+            let value = parse(r#"{"id": 123456789012345678901234567890, "values": [1.50]}"#);
+
+            let mut expected = RawValueMap::new();
+            expected.insert(
+                "id".to_string(),
+                RawValue::Number("123456789012345678901234567890".to_string()),
+            );
+            expected.insert(
+                "values".to_string(),
+                RawValue::Array(vec![RawValue::Number("1.50".to_string())]),
+            );
+            assert_eq!(value, RawValue::Object(expected));
+        }
+
+        #[test]
+        fn serialize() {
+            let json = "[42,42.1,123456789012345678901234567890,0.10000000000000000001]";
+            assert_eq!(serde_json::to_string(&parse(json)).unwrap(), json);
+        }
+    }
+
+    mod value {
+        use super::*;
+
+        mod from_json_string {
+            use super::*;
+
+            #[test]
+            fn object() {
+                let mut expected = ValueMap::new();
+                expected.insert("key".to_string(), Value::String("value".to_string()));
+                assert_eq!(
+                    Value::from_json_string(r#"{"key": "value"}"#),
+                    Some(Value::Object(expected))
+                );
+            }
+
+            #[test]
+            fn array() {
+                assert_eq!(
+                    Value::from_json_string("[1, true]"),
+                    Some(Value::Array(vec![Value::Integer(1), Value::Boolean(true)]))
+                );
+            }
+
+            #[test]
+            fn scalar() {
+                assert_eq!(Value::from_json_string("42"), None);
+                assert_eq!(Value::from_json_string(r#""string""#), None);
+                assert_eq!(Value::from_json_string("null"), None);
+            }
+
+            #[test]
+            fn invalid() {
+                assert_eq!(Value::from_json_string("{invalid"), None);
+                assert_eq!(Value::from_json_string("plain string"), None);
+            }
+        }
+    }
+
+    mod is_empty {
+        use super::*;
+
+        #[test]
+        fn empty() {
+            assert!(Value::Null.is_empty());
+            assert!(Value::String(String::new()).is_empty());
+            assert!(Value::Array(Vec::new()).is_empty());
+            assert!(Value::Object(ValueMap::new()).is_empty());
+        }
+
+        #[test]
+        fn not_empty() {
+            assert!(!Value::String(" ".to_string()).is_empty());
+            assert!(!Value::Integer(0).is_empty());
+            assert!(!Value::Boolean(false).is_empty());
+            assert!(!Value::Array(vec![Value::Null]).is_empty());
+            assert!(!Value::Sensitive.is_empty());
+        }
+    }
+
+    mod diff_arrays {
+        use super::*;
+
+        fn values(values: &[i64]) -> Vec<Value> {
+            values.iter().map(|value| Value::Integer(*value)).collect()
+        }
+
+        #[test]
+        fn equal() {
+            let result = diff_arrays(&values(&[1, 2]), &values(&[1, 2]));
+            assert_eq!(
+                result,
+                vec![
+                    ArrayDiffOp::Unchanged {
+                        before: 0,
+                        after: 0
+                    },
+                    ArrayDiffOp::Unchanged {
+                        before: 1,
+                        after: 1
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn empty() {
+            assert_eq!(diff_arrays(&[], &[]), vec![]);
+            assert_eq!(
+                diff_arrays(&values(&[1]), &[]),
+                vec![ArrayDiffOp::Removed { before: 0 }]
+            );
+            assert_eq!(
+                diff_arrays(&[], &values(&[1])),
+                vec![ArrayDiffOp::Added { after: 0 }]
+            );
+        }
+
+        #[test]
+        fn changed() {
+            let result = diff_arrays(&values(&[1, 2, 3]), &values(&[1, 4, 3]));
+            assert_eq!(
+                result,
+                vec![
+                    ArrayDiffOp::Unchanged {
+                        before: 0,
+                        after: 0
+                    },
+                    ArrayDiffOp::Changed {
+                        before: 1,
+                        after: 1
+                    },
+                    ArrayDiffOp::Unchanged {
+                        before: 2,
+                        after: 2
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn added_removed() {
+            let result = diff_arrays(&values(&[1, 2, 3]), &values(&[2, 3, 4, 5]));
+            assert_eq!(
+                result,
+                vec![
+                    ArrayDiffOp::Removed { before: 0 },
+                    ArrayDiffOp::Unchanged {
+                        before: 1,
+                        after: 0
+                    },
+                    ArrayDiffOp::Unchanged {
+                        before: 2,
+                        after: 1
+                    },
+                    ArrayDiffOp::Added { after: 2 },
+                    ArrayDiffOp::Added { after: 3 },
+                ]
+            );
+        }
+
+        #[test]
+        fn reordered() {
+            let result = diff_arrays(&values(&[1, 2, 3]), &values(&[2, 3, 1]));
+            assert_eq!(
+                result,
+                vec![
+                    ArrayDiffOp::Removed { before: 0 },
+                    ArrayDiffOp::Unchanged {
+                        before: 1,
+                        after: 0
+                    },
+                    ArrayDiffOp::Unchanged {
+                        before: 2,
+                        after: 1
+                    },
+                    ArrayDiffOp::Added { after: 2 },
+                ]
+            );
+        }
+
+        #[test]
+        fn large() {
+            let before: Vec<i64> = (0..2000).collect();
+            let mut after: Vec<i64> = (1..2000).collect();
+            after.push(2000);
+            after.push(2001);
+            let result = diff_arrays(&values(&before), &values(&after));
+
+            assert_eq!(result.len(), 2001);
+            assert_eq!(
+                result[0],
+                ArrayDiffOp::Changed {
+                    before: 0,
+                    after: 0
+                }
+            );
+            assert_eq!(result[2000], ArrayDiffOp::Added { after: 2000 });
+        }
+    }
+
+    mod diff_sets {
+        use super::*;
+
+        fn values(values: &[i64]) -> Vec<Value> {
+            values.iter().map(|value| Value::Integer(*value)).collect()
+        }
+
+        #[test]
+        fn reordered() {
+            let result = diff_sets(&values(&[1, 2, 3]), &values(&[2, 3, 1]));
+            assert!(result
+                .iter()
+                .all(|op| matches!(op, ArrayDiffOp::Unchanged { .. })));
+            assert_eq!(result.len(), 3);
+        }
+
+        #[test]
+        fn added_removed() {
+            let result = diff_sets(&values(&[1, 2, 2]), &values(&[2, 4]));
+            assert_eq!(
+                result,
+                vec![
+                    ArrayDiffOp::Unchanged {
+                        before: 1,
+                        after: 0
+                    },
+                    ArrayDiffOp::Removed { before: 0 },
+                    ArrayDiffOp::Removed { before: 2 },
+                    ArrayDiffOp::Added { after: 1 },
+                ]
+            );
+        }
+    }
+
+    mod diff_values {
+        use super::*;
+
+        fn node(path: &[&str], kind: DiffKind) -> DiffNode {
+            DiffNode {
+                path: path.iter().map(ToString::to_string).collect(),
+                kind,
+                before: None,
+                after: None,
+                sensitive: false,
+                unknown: false,
+                forces_replacement: false,
+                ignored: false,
+                cosmetic: false,
+                description: None,
+                children: Vec::new(),
+            }
+        }
+
+        #[test]
+        fn scalars() {
+            let mut before = ValueMap::new();
+            before.insert("changed".to_string(), Value::Integer(1));
+            before.insert("removed".to_string(), Value::Integer(2));
+            before.insert("unchanged".to_string(), Value::Integer(3));
+            before.insert("sensitive".to_string(), Value::Sensitive);
+            let mut after = ValueMap::new();
+            after.insert("added".to_string(), Value::Integer(4));
+            after.insert("changed".to_string(), Value::Integer(5));
+            after.insert("removed".to_string(), Value::Null);
+            after.insert("unchanged".to_string(), Value::Integer(3));
+            after.insert("sensitive".to_string(), Value::Sensitive);
+
+            let result = diff_values(
+                Some(&before),
+                Some(&after),
+                &Markers::default(),
+                &[vec!["changed".to_string()]],
+                &[],
+                false,
+                None,
+            );
+
+            let expected = vec![
+                DiffNode {
+                    after: Some(Value::Integer(4)),
+                    ..node(&["added"], DiffKind::Added)
+                },
+                DiffNode {
+                    before: Some(Value::Integer(1)),
+                    after: Some(Value::Integer(5)),
+                    forces_replacement: true,
+                    ..node(&["changed"], DiffKind::Changed)
+                },
+                DiffNode {
+                    before: Some(Value::Integer(2)),
+                    ..node(&["removed"], DiffKind::Removed)
+                },
+                DiffNode {
+                    before: Some(Value::Sensitive),
+                    after: Some(Value::Sensitive),
+                    sensitive: true,
+                    ..node(&["sensitive"], DiffKind::Unchanged)
+                },
+                DiffNode {
+                    before: Some(Value::Integer(3)),
+                    after: Some(Value::Integer(3)),
+                    ..node(&["unchanged"], DiffKind::Unchanged)
+                },
+            ];
+            assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn nested() {
+            let mut before_inner = ValueMap::new();
+            before_inner.insert("key".to_string(), Value::String("old".to_string()));
+            let mut after_inner = ValueMap::new();
+            after_inner.insert("key".to_string(), Value::String("new".to_string()));
+            let mut before = ValueMap::new();
+            before.insert(
+                "list".to_string(),
+                Value::Array(vec![Value::Integer(1), Value::Object(before_inner)]),
+            );
+            let mut after = ValueMap::new();
+            after.insert(
+                "list".to_string(),
+                Value::Array(vec![Value::Integer(1), Value::Object(after_inner)]),
+            );
+
+            let result = diff_values(
+                Some(&before),
+                Some(&after),
+                &Markers::default(),
+                &[],
+                &[],
+                false,
+                None,
+            );
+
+            let expected = vec![DiffNode {
+                children: vec![
+                    DiffNode {
+                        before: Some(Value::Integer(1)),
+                        after: Some(Value::Integer(1)),
+                        ..node(&["list", "0"], DiffKind::Unchanged)
+                    },
+                    DiffNode {
+                        children: vec![DiffNode {
+                            before: Some(Value::String("old".to_string())),
+                            after: Some(Value::String("new".to_string())),
+                            ..node(&["list", "1", "key"], DiffKind::Changed)
+                        }],
+                        ..node(&["list", "1"], DiffKind::Changed)
+                    },
+                ],
+                ..node(&["list"], DiffKind::Changed)
+            }];
+            assert_eq!(result, expected);
+        }
+
+        #[test]
+        fn normalize_empty() {
+            let mut before = ValueMap::new();
+            before.insert("string".to_string(), Value::String(String::new()));
+            before.insert("array".to_string(), Value::Array(Vec::new()));
+            before.insert("changed".to_string(), Value::String(String::new()));
+            let mut after = ValueMap::new();
+            after.insert("string".to_string(), Value::Null);
+            after.insert("array".to_string(), Value::Object(ValueMap::new()));
+            after.insert("changed".to_string(), Value::String("value".to_string()));
+
+            let result = diff_values(
+                Some(&before),
+                Some(&after),
+                &Markers::default(),
+                &[],
+                &[],
+                true,
+                None,
+            );
+
+            let cosmetic: Vec<(String, bool)> = result
+                .iter()
+                .map(|node| (node.path.join("."), node.cosmetic))
+                .collect();
+            assert_eq!(
+                cosmetic,
+                vec![
+                    ("array".to_string(), true),
+                    ("changed".to_string(), false),
+                    ("string".to_string(), true),
+                ]
+            );
+            assert_eq!(
+                result.iter().filter(|node| node.is_significant()).count(),
+                1
+            );
+
+            let result = diff_values(
+                Some(&before),
+                Some(&after),
+                &Markers::default(),
+                &[],
+                &[],
+                false,
+                None,
+            );
+            assert!(result.iter().all(|node| !node.cosmetic));
+        }
+
+        #[test]
+        fn unknown() {
+            let mut before = ValueMap::new();
+            before.insert("id".to_string(), Value::String("id".to_string()));
+            let mut after_unknown = std::collections::HashMap::new();
+            after_unknown.insert("id".to_string(), BoolValue::Boolean(true));
+            after_unknown.insert("arn".to_string(), BoolValue::Boolean(true));
+
+            let result = diff_values(
+                Some(&before),
+                Some(&ValueMap::new()),
+                &Markers {
+                    unknown: BoolValue::Object(after_unknown),
+                    ..Markers::default()
+                },
+                &[],
+                &[],
+                false,
+                None,
+            );
+
+            let expected = vec![
+                DiffNode {
+                    unknown: true,
+                    ..node(&["arn"], DiffKind::Added)
+                },
+                DiffNode {
+                    before: Some(Value::String("id".to_string())),
+                    unknown: true,
+                    ..node(&["id"], DiffKind::Changed)
+                },
+            ];
+            assert_eq!(result, expected);
+        }
+    }
+
+    mod path_matches {
+        use super::*;
+
+        fn path(path: &str) -> Vec<String> {
+            path.split('.').map(ToString::to_string).collect()
+        }
+
+        #[test]
+        fn exact() {
+            assert!(path_matches(&path("tags_all"), &path("tags_all")));
+            assert!(!path_matches(&path("tags_all"), &path("tags")));
+        }
+
+        #[test]
+        fn nested() {
+            assert!(path_matches(&path("tags"), &path("tags.Name")));
+            assert!(!path_matches(&path("tags.Name"), &path("tags")));
+        }
+
+        #[test]
+        fn wildcard() {
+            assert!(path_matches(
+                &path("ingress.*.description"),
+                &path("ingress.1.description")
+            ));
+            assert!(!path_matches(
+                &path("ingress.*.description"),
+                &path("ingress.1.port")
+            ));
+        }
+    }
+
+    mod ignore_rule {
+        use super::*;
+
+        #[test]
+        fn global() {
+            let rule = IgnoreRule::from_str("tags.Name").unwrap();
+            assert_eq!(rule.resource_type, None);
+            assert_eq!(rule.path, vec!["tags".to_string(), "Name".to_string()]);
+            assert!(rule.applies_to("aws_s3_bucket"));
+        }
+
+        #[test]
+        fn resource_type() {
+            let rule = IgnoreRule::from_str("aws_s3_bucket:etag").unwrap();
+            assert_eq!(rule.resource_type, Some("aws_s3_bucket".to_string()));
+            assert_eq!(rule.path, vec!["etag".to_string()]);
+            assert!(rule.applies_to("aws_s3_bucket"));
+            assert!(!rule.applies_to("aws_s3_object"));
+        }
+
+        #[test]
+        fn invalid() {
+            for rule in ["", "tags.", ":etag", "aws_s3_bucket:"] {
+                assert_eq!(
+                    IgnoreRule::from_str(rule).unwrap_err().to_string(),
+                    format!("Invalid ignore rule: {rule}")
+                );
+            }
+        }
+    }
+
     mod change {
         use super::*;
 
@@ -573,13 +2206,18 @@ pub mod tests {
             ) -> RawResourceChange {
                 RawResourceChange {
                     address: "address".to_string(),
+                    module_address: None,
+                    mode: "managed".to_string(),
+                    type_: "type".to_string(),
                     name: "name".to_string(),
                     change: RawResourceChangeChange {
                         actions: vec![RawResourceChangeChangeAction::Create],
                         before,
                         after,
+                        after_unknown: None,
                         before_sensitive,
                         after_sensitive,
+                        replace_paths: None,
                     },
                 }
             }
@@ -589,28 +2227,126 @@ pub mod tests {
                 let path = utils::test::get_test_data_file_path("plans/artificial/full.json");
                 let raw = RawPlan::from_file(&path).unwrap();
 
-                let _ = Plan::from_raw(raw);
+                let _ = Plan::from_raw(raw, &Options::default());
             }
 
             #[test]
             fn empty() {
                 let raw = get_raw(None, None, None, None);
-                let change = Change::from_raw(raw);
+                let change = Change::from_raw(raw, &Options::default());
 
                 assert_eq!(change.address, "address");
+                assert_eq!(change.mode, "managed");
+                assert_eq!(change.type_, "type");
                 assert_eq!(change.name, "name");
                 assert_eq!(change.action, Action::Create);
                 assert_eq!(change.before, None);
                 assert_eq!(change.after, None);
             }
 
+            #[test]
+            fn cosmetic() {
+                let json = get_test_plan_json(&PlanType::Update);
+                let raw = RawPlan::from_str(&json).unwrap();
+                let raw_change = raw.resource_changes.unwrap().remove(0);
+
+                let change = Change::from_raw(raw_change.clone(), &Options::default());
+                assert!(change.ignored_paths.is_empty());
+                assert!(!change.cosmetic);
+
+                let options = Options {
+                    ignore_rules: vec![
+                        IgnoreRule::from_str("terraform_data:input").unwrap(),
+                        IgnoreRule::from_str("other_type:output").unwrap(),
+                    ],
+                    ..Options::default()
+                };
+                let change = Change::from_raw(raw_change.clone(), &options);
+                assert_eq!(change.ignored_paths, vec![vec!["input".to_string()]]);
+                assert!(!change.cosmetic);
+
+                let options = Options {
+                    ignore_rules: vec![
+                        IgnoreRule::from_str("input").unwrap(),
+                        IgnoreRule::from_str("output").unwrap(),
+                    ],
+                    ..Options::default()
+                };
+                let change = Change::from_raw(raw_change, &options);
+                assert!(change.cosmetic);
+                assert!(change
+                    .diff
+                    .iter()
+                    .all(|node| node.ignored || node.kind == DiffKind::Unchanged));
+            }
+
+            #[test]
+            fn schema() {
+                let json = utils::test::get_test_data_file_contents("plans/artificial/schema.json");
+                let mut raw_changes = RawPlan::from_str(&json).unwrap().resource_changes.unwrap();
+                let path = utils::test::get_test_data_file_path("schemas/providers.json");
+                let options = Options {
+                    schemas: schema::Registry::from_file(&path).unwrap(),
+                    ..Options::default()
+                };
+
+                let change = Change::from_raw(raw_changes.remove(0), &options);
+                let after = change.after.unwrap();
+                assert_eq!(after.get("password"), Some(&Value::Sensitive));
+                let Some(Value::Array(endpoints)) = after.get("endpoints") else {
+                    panic!("endpoints should be an array");
+                };
+                let Value::Object(endpoint) = &endpoints[0] else {
+                    panic!("endpoint should be an object");
+                };
+                assert_eq!(endpoint.get("token"), Some(&Value::Sensitive));
+                assert_eq!(
+                    endpoint.get("url"),
+                    Some(&Value::String("https://new.example.com".to_string()))
+                );
+
+                let node = |key: &str| change.diff.iter().find(|node| node.path == [key]).unwrap();
+                assert_eq!(node("security_groups").kind, DiffKind::Unchanged);
+                assert_eq!(node("ingress").children.len(), 3);
+                assert_eq!(
+                    node("name").description,
+                    Some("Name of the resource".to_string())
+                );
+
+                let change = Change::from_raw(raw_changes.remove(0), &options);
+                assert!(options.schemas.get(&change.mode, &change.type_).is_some());
+
+                let change = Change::from_raw(get_raw(None, None, None, None), &options);
+                assert!(options.schemas.get(&change.mode, &change.type_).is_none());
+            }
+
+            #[test]
+            fn sensitive_update() {
+                let path =
+                    utils::test::get_test_data_file_path("plans/artificial/sensitive-update.json");
+                let mut raw_changes = RawPlan::from_file(&path).unwrap().resource_changes.unwrap();
+
+                let change = Change::from_raw(raw_changes.remove(0), &Options::default());
+                let node = change
+                    .diff
+                    .iter()
+                    .find(|node| node.path == ["password"])
+                    .unwrap();
+                assert_eq!(node.kind, DiffKind::Changed);
+                assert!(node.sensitive);
+                assert!(node.is_significant());
+                assert_eq!(node.before, Some(Value::Sensitive));
+                assert_eq!(node.after, Some(Value::Sensitive));
+                assert!(!change.cosmetic);
+            }
+
             #[test]
             fn sesitive_before_true() {
                 let mut before = RawValueMap::new();
                 before.insert("key".to_string(), RawValue::String("value".to_string()));
 
                 let raw = get_raw(Some(before), None, Some(BoolValue::Boolean(true)), None);
-                let change = Change::from_raw(raw);
+                let change = Change::from_raw(raw, &Options::default());
 
                 let mut expected_before = ValueMap::new();
                 expected_before.insert("key".to_string(), Value::Sensitive);
@@ -623,7 +2359,7 @@ pub mod tests {
                 after.insert("key".to_string(), RawValue::String("value".to_string()));
 
                 let raw = get_raw(None, Some(after), None, Some(BoolValue::Boolean(true)));
-                let change = Change::from_raw(raw);
+                let change = Change::from_raw(raw, &Options::default());
 
                 let mut expected_after = ValueMap::new();
                 expected_after.insert("key".to_string(), Value::Sensitive);
@@ -636,7 +2372,7 @@ pub mod tests {
                 before.insert("key".to_string(), RawValue::String("value".to_string()));
 
                 let raw = get_raw(Some(before), None, Some(BoolValue::Boolean(false)), None);
-                let change = Change::from_raw(raw);
+                let change = Change::from_raw(raw, &Options::default());
                 let mut expected_before = ValueMap::new();
                 expected_before.insert("key".to_string(), Value::String("value".to_string()));
 
@@ -649,7 +2385,7 @@ pub mod tests {
                 after.insert("key".to_string(), RawValue::String("value".to_string()));
 
                 let raw = get_raw(None, Some(after), None, Some(BoolValue::Boolean(false)));
-                let change = Change::from_raw(raw);
+                let change = Change::from_raw(raw, &Options::default());
                 let mut after = ValueMap::new();
                 after.insert("key".to_string(), Value::String("value".to_string()));
 
@@ -670,7 +2406,7 @@ pub mod tests {
                     Some(BoolValue::Object(before_sensitive)),
                     None,
                 );
-                let change = Change::from_raw(raw);
+                let change = Change::from_raw(raw, &Options::default());
 
                 let mut expected_before = ValueMap::new();
                 expected_before.insert("key".to_string(), Value::Sensitive);
@@ -691,7 +2427,7 @@ pub mod tests {
                     None,
                     Some(BoolValue::Object(after_sensitive)),
                 );
-                let change = Change::from_raw(raw);
+                let change = Change::from_raw(raw, &Options::default());
 
                 let mut expected_after = ValueMap::new();
                 expected_after.insert("key".to_string(), Value::Sensitive);
@@ -727,7 +2463,7 @@ pub mod tests {
                     None,
                     Some(BoolValue::Object(after_sensitive)),
                 );
-                let change = Change::from_raw(raw);
+                let change = Change::from_raw(raw, &Options::default());
 
                 let mut expected_after = ValueMap::new();
                 expected_after.insert(
@@ -771,7 +2507,7 @@ pub mod tests {
                     Some(BoolValue::Object(before_sensitive)),
                     None,
                 );
-                let change = Change::from_raw(raw);
+                let change = Change::from_raw(raw, &Options::default());
 
                 let mut expected_before = ValueMap::new();
                 expected_before.insert(
@@ -799,7 +2535,7 @@ pub mod tests {
                     $(
                         #[test]
                         fn $name() {
-                            get_test_plan(&$plan_type);
+                            let _ = get_test_plan(&$plan_type);
                         }
                     )*
                 };
@@ -862,6 +2598,361 @@ pub mod tests {
         }
     }
 
+    mod filter {
+        use super::*;
+
+        fn get_raw_change(
+            address: &str,
+            mode: &str,
+            type_: &str,
+            action: &str,
+        ) -> RawResourceChange {
+            serde_json::from_value(serde_json::json!({
+                "address": address,
+                "mode": mode,
+                "type": type_,
+                "name": "test",
+                "change": {"actions": [action], "before": null, "after": null}
+            }))
+            .unwrap()
+        }
+
+        #[test]
+        fn action_from_str() {
+            assert_eq!(Action::from_str("replace").unwrap(), Action::DeleteCreate);
+            assert_eq!(Action::from_str("no-op").unwrap(), Action::NoOp);
+            for action in Action::ALL {
+                assert_eq!(Action::from_str(action.as_str()).unwrap(), action);
+            }
+            assert_eq!(
+                Action::from_str("invalid").unwrap_err().to_string(),
+                "Invalid action: invalid"
+            );
+        }
+
+        #[test]
+        fn default() {
+            let raw = get_raw_change("aws_s3_bucket.test", "managed", "aws_s3_bucket", "no-op");
+            assert!(Filter::default().includes(&raw));
+        }
+
+        #[test]
+        fn exclude_actions() {
+            let filter = Filter {
+                exclude_actions: vec![Action::NoOp, Action::Read],
+                ..Filter::default()
+            };
+            assert!(!filter.includes(&get_raw_change("a.b", "managed", "a", "no-op")));
+            assert!(!filter.includes(&get_raw_change("data.a.b", "data", "a", "read")));
+            assert!(filter.includes(&get_raw_change("a.b", "managed", "a", "create")));
+        }
+
+        #[test]
+        fn include_addresses() {
+            let filter = Filter {
+                include_addresses: vec![glob::Pattern::new("module.app.*").unwrap()],
+                ..Filter::default()
+            };
+            assert!(filter.includes(&get_raw_change("module.app.a.b", "managed", "a", "create")));
+            assert!(filter.includes(&get_raw_change(
+                "module.app.module.db.a.b",
+                "managed",
+                "a",
+                "create"
+            )));
+            assert!(!filter.includes(&get_raw_change("module.web.a.b", "managed", "a", "create")));
+        }
+
+        #[test]
+        fn exclude_types() {
+            let filter = Filter {
+                exclude_types: vec![glob::Pattern::new("random_*").unwrap()],
+                ..Filter::default()
+            };
+            assert!(!filter.includes(&get_raw_change(
+                "random_id.a",
+                "managed",
+                "random_id",
+                "create"
+            )));
+            assert!(filter.includes(&get_raw_change(
+                "null_resource.a",
+                "managed",
+                "null_resource",
+                "create"
+            )));
+        }
+
+        #[test]
+        fn exclude_modes() {
+            let filter = Filter {
+                exclude_modes: vec!["data".to_string()],
+                ..Filter::default()
+            };
+            assert!(!filter.includes(&get_raw_change("data.a.b", "data", "a", "read")));
+            assert!(filter.includes(&get_raw_change("a.b", "managed", "a", "read")));
+        }
+
+        #[test]
+        fn plan() {
+            let json = get_test_plan_json(&PlanType::NoOp);
+            let options = Options {
+                filter: Filter {
+                    exclude_actions: vec![Action::NoOp],
+                    ..Filter::default()
+                },
+                ..Options::default()
+            };
+            let plan = Plan::from_raw(RawPlan::from_str(&json).unwrap(), &options);
+            assert!(plan.changes.is_empty());
+            assert!(plan.unique_actions.is_empty());
+            assert_eq!(plan.counts, ActionCounts::default());
+        }
+    }
+
+    mod module {
+        use super::*;
+
+        #[test]
+        fn module_path_root() {
+            assert!(module_path("aws_s3_bucket.this").is_empty());
+            assert!(module_path("").is_empty());
+        }
+
+        #[test]
+        fn module_path_nested() {
+            assert_eq!(
+                module_path("module.app.module.db[0].aws_s3_bucket.this"),
+                vec!["module.app".to_string(), "module.db[0]".to_string()]
+            );
+            assert_eq!(
+                module_path("module.app.module.db[\"eu.west\"]"),
+                vec![
+                    "module.app".to_string(),
+                    "module.db[\"eu.west\"]".to_string()
+                ]
+            );
+            assert_eq!(
+                module_path("module.app.data.module.this"),
+                vec!["module.app".to_string()]
+            );
+        }
+
+        #[test]
+        fn from_changes() {
+            let path = utils::test::get_test_data_file_path("plans/artificial/modules.json");
+            let raw = RawPlan::from_file(&path).unwrap();
+            let options = Options {
+                group_by_module: true,
+                ..Options::default()
+            };
+            let plan = Plan::from_raw(raw, &options);
+
+            assert_eq!(
+                plan.modules,
+                Some(Module {
+                    address: String::new(),
+                    unique_actions: vec![
+                        Action::Create,
+                        Action::Read,
+                        Action::Update,
+                        Action::Delete
+                    ],
+                    changes: vec![0],
+                    groups: vec![],
+                    modules: vec![
+                        Module {
+                            address: "module.app".to_string(),
+                            unique_actions: vec![Action::Update, Action::Delete],
+                            changes: vec![1],
+                            groups: vec![],
+                            modules: vec![Module {
+                                address: "module.app.module.db[\"eu.west\"]".to_string(),
+                                unique_actions: vec![Action::Delete],
+                                changes: vec![2],
+                                groups: vec![],
+                                modules: vec![],
+                            }],
+                        },
+                        Module {
+                            address: "module.web".to_string(),
+                            unique_actions: vec![Action::Read],
+                            changes: vec![3],
+                            groups: vec![],
+                            modules: vec![],
+                        },
+                    ],
+                })
+            );
+        }
+
+        #[test]
+        fn cosmetic() {
+            let path = utils::test::get_test_data_file_path("plans/artificial/modules.json");
+            let raw = RawPlan::from_file(&path).unwrap();
+            let options = Options {
+                ignore_rules: vec![IgnoreRule::from_str("test_type:name").unwrap()],
+                group_by_module: true,
+                ..Options::default()
+            };
+            let plan = Plan::from_raw(raw, &options);
+            let modules = plan.modules.unwrap();
+
+            assert!(plan.changes[1].cosmetic);
+            assert_eq!(modules.modules[0].address, "module.app");
+            assert!(modules.modules[0].changes.is_empty());
+            assert_eq!(modules.modules[0].unique_actions, vec![Action::Delete]);
+        }
+
+        #[test]
+        fn not_grouped() {
+            let plan = get_test_plan(&PlanType::Create);
+            assert_eq!(plan.modules, None);
+        }
+    }
+
+    mod instance_group {
+        use super::*;
+
+        #[test]
+        fn split_instance_key_default() {
+            assert_eq!(
+                split_instance_key("aws_s3_bucket.this[\"a.b\"]"),
+                ("aws_s3_bucket.this".to_string(), "[\"a.b\"]".to_string())
+            );
+            assert_eq!(
+                split_instance_key("module.app[0].aws_s3_bucket.this[1]"),
+                (
+                    "module.app[0].aws_s3_bucket.this".to_string(),
+                    "[1]".to_string()
+                )
+            );
+            assert_eq!(
+                split_instance_key("module.app[0].aws_s3_bucket.this"),
+                (
+                    "module.app[0].aws_s3_bucket.this".to_string(),
+                    String::new()
+                )
+            );
+        }
+
+        #[test]
+        fn from_changes() {
+            let path = utils::test::get_test_data_file_path("plans/artificial/instances.json");
+            let raw = RawPlan::from_file(&path).unwrap();
+            let options = Options {
+                group_instances: true,
+                ..Options::default()
+            };
+            let plan = Plan::from_raw(raw, &options);
+            let groups = plan.groups.unwrap();
+
+            assert_eq!(
+                groups
+                    .iter()
+                    .map(|group| (
+                        group.address.as_str(),
+                        group.changes.clone(),
+                        group.identical
+                    ))
+                    .collect::<Vec<_>>(),
+                vec![
+                    // Differ in the unchanged `name` only
+                    ("test_type.for_each", vec![0, 1, 2], true),
+                    ("test_type.count", vec![3, 4], false),
+                    ("test_type.single", vec![5], true),
+                    ("module.app.test_type.for_each", vec![6, 7], false),
+                    ("test_type.replicas", vec![8, 9], true),
+                ]
+            );
+            assert_eq!(groups[0].keys, vec!["[\"a\"]", "[\"b\"]", "[\"c\"]"]);
+            assert_eq!(groups[1].keys, vec!["[0]", "[1]"]);
+            assert_eq!(groups[2].keys, vec![""]);
+        }
+
+        #[test]
+        fn modules() {
+            let path = utils::test::get_test_data_file_path("plans/artificial/instances.json");
+            let raw = RawPlan::from_file(&path).unwrap();
+            let options = Options {
+                group_instances: true,
+                group_by_module: true,
+                ..Options::default()
+            };
+            let plan = Plan::from_raw(raw, &options);
+            let modules = plan.modules.unwrap();
+
+            assert_eq!(modules.groups, vec![0, 1, 2, 4]);
+            assert_eq!(modules.modules[0].groups, vec![3]);
+        }
+    }
+
+    mod action_counts {
+        use super::*;
+
+        #[test]
+        fn from_actions() {
+            let actions = [
+                Action::Create,
+                Action::Create,
+                Action::Update,
+                Action::DeleteCreate,
+                Action::Delete,
+                Action::Read,
+                Action::NoOp,
+                Action::Unknown,
+            ];
+            assert_eq!(
+                ActionCounts::from_actions(&actions),
+                ActionCounts {
+                    create: 2,
+                    update: 1,
+                    delete_create: 1,
+                    delete: 1,
+                    read: 1,
+                    no_op: 1,
+                    unknown: 1,
+                    add: 3,
+                    change: 1,
+                    destroy: 2,
+                }
+            );
+        }
+
+        #[test]
+        fn plan() {
+            let plan = get_test_plan(&PlanType::DeleteCreate);
+            assert_eq!(
+                plan.counts,
+                ActionCounts {
+                    delete_create: 1,
+                    add: 1,
+                    destroy: 1,
+                    ..ActionCounts::default()
+                }
+            );
+        }
+
+        #[test]
+        fn data() {
+            let data = get_test_data();
+            assert_eq!(
+                data.counts,
+                ActionCounts {
+                    create: 1,
+                    update: 1,
+                    delete_create: 2,
+                    delete: 1,
+                    no_op: 1,
+                    add: 3,
+                    change: 1,
+                    destroy: 3,
+                    ..ActionCounts::default()
+                }
+            );
+        }
+    }
+
     mod data {
         use super::*;
 
@@ -871,21 +2962,21 @@ pub mod tests {
             #[test]
             fn default() {
                 let files = get_test_data_files();
-                let data = Data::from_files(&files).unwrap();
+                let data = Data::from_files(&files, &Options::default()).unwrap();
                 assert_eq!(data, get_test_data());
             }
 
             #[test]
             fn glob() {
                 let files = vec!["tests/data/plans/*/terraform.tfplan.json".to_string()];
-                let data = Data::from_files(&files).unwrap();
+                let data = Data::from_files(&files, &Options::default()).unwrap();
                 assert_eq!(data, get_test_data());
             }
 
             #[test]
             fn invalid_glob() {
                 let files = vec!["*****".to_string()];
-                let data = Data::from_files(&files);
+                let data = Data::from_files(&files, &Options::default());
                 assert_eq!(
                     data.unwrap_err().full_message(),
                     "Failed to read file(*****), invalid glob. Pattern syntax error near position 2: wildcards are either regular `*` or recursive `**`"
@@ -894,7 +2985,7 @@ pub mod tests {
 
             #[test]
             fn no_files() {
-                let data = Data::from_files(&["invalid path".to_string()]);
+                let data = Data::from_files(&["invalid path".to_string()], &Options::default());
                 assert_eq!(
                     data.unwrap_err().to_string(),
                     "Failed to read file(invalid path). No files found"
@@ -0,0 +1,79 @@
+use crate::utils;
+use assert_cmd::prelude::*;
+use std::process::Command;
+
+#[test]
+fn default() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result = utils::get_test_data_file_contents("csv/default.csv");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("csv");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn tab() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result = utils::get_test_data_file_contents("csv/tab.tsv");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("csv");
+    cmd.arg("--delimiter").arg("tab");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn modules() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result = utils::get_test_data_file_contents("csv/modules.csv");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("csv");
+    cmd.arg("--file").arg(utils::get_test_data_file_path(
+        "plans/artificial/modules.json",
+    ));
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_delimiter() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("csv");
+    cmd.arg("--delimiter").arg("ab");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().failure();
+    cmd.assert().stdout("");
+    cmd.assert()
+        .stderr("Invalid delimiter(ab). Invalid delimiter: ab\n");
+    cmd.assert().code(64);
+
+    Ok(())
+}
@@ -49,6 +49,47 @@ fn glob() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn counts() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("custom");
+    cmd.arg("--file").arg(utils::get_test_data_file_path(
+        "plans/delete-create/terraform.tfplan.json",
+    ));
+    cmd.arg("--template").arg(
+        "{% for _, plan in data.plans %}{{ plan.counts.delete_create }}{% endfor %} {{ data.counts.add }}/{{ data.counts.change }}/{{ data.counts.destroy }}",
+    );
+
+    cmd.assert().success();
+    cmd.assert().stdout("1 1/0/1\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn include_address() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("custom");
+    cmd.arg("--include-address").arg("null_resource.*");
+    cmd.arg("--exclude-mode").arg("data");
+    cmd.arg("--template").arg(
+        "{% for _, plan in data.plans %}{% for change in plan.changes %}{{ change.address }}{% endfor %}{% endfor %}",
+    );
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout("null_resource.foo-bar\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
 #[test]
 fn invalid_engine() -> Result<(), Box<dyn std::error::Error>> {
     let template = utils::get_test_data_file_contents("tera/templates/custom");
@@ -114,3 +155,24 @@ fn test_invalid_template() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn symbols() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("custom");
+    cmd.arg("--symbols").arg("words");
+
+    cmd.arg("--file").arg(utils::get_test_data_file_path(
+        "plans/delete-create/terraform.tfplan.json",
+    ));
+    cmd.arg("--template").arg(
+        "{% for name, plan in data.plans %}{{ render_actions(actions=plan.unique_actions) }}{{ plan.changes | length }}{% endfor %}",
+    );
+
+    cmd.assert().success();
+    cmd.assert().stdout("[replace] 1\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use crate::utils;
 use assert_cmd::prelude::*;
+use predicates::prelude::*;
 use std::process::Command;
 
 #[test]
@@ -43,6 +44,419 @@ fn changed() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn terraform_style() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/terraform.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--style").arg("terraform");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn summary() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/summary.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--summary");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn ascii() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/ascii.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--summary");
+    cmd.arg("--symbols").arg("ascii");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_symbols() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--symbols").arg("invalid");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().failure();
+    cmd.assert().stdout("");
+    cmd.assert()
+        .stderr("Invalid symbols(invalid). Invalid symbols: invalid\n");
+    cmd.assert().code(64);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_style() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--style").arg("invalid");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().failure();
+    cmd.assert().stdout("");
+    cmd.assert()
+        .stderr("Invalid style(invalid). Invalid values style: invalid\n");
+    cmd.assert().code(64);
+
+    Ok(())
+}
+
+#[test]
+fn ignore() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/ignore.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--ignore").arg("terraform_data:input");
+    cmd.arg("--ignore").arg("output");
+    cmd.arg("--ignore").arg("triggers.*");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_ignore() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--ignore").arg("tags..name");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().failure();
+    cmd.assert().stdout("");
+    cmd.assert()
+        .stderr("Invalid ignore rule(tags..name). Invalid ignore rule: tags..name\n");
+    cmd.assert().code(64);
+
+    Ok(())
+}
+
+#[test]
+fn config() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/ignore.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--config")
+        .arg(utils::get_test_data_file_path("config/ignore.json"));
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn config_symbols() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/ascii.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--summary");
+    cmd.arg("--config")
+        .arg(utils::get_test_data_file_path("config/symbols.json"));
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_config() -> Result<(), Box<dyn std::error::Error>> {
+    let path = utils::get_test_data_file_path("config/invalid.json");
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--config").arg(&path);
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().failure();
+    cmd.assert().stdout("");
+    cmd.assert().stderr(predicates::str::starts_with(format!(
+        "Failed to load config. Failed to parse file({path})."
+    )));
+    cmd.assert().code(78);
+
+    Ok(())
+}
+
+#[test]
+fn normalize_empty() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/normalize_empty.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--normalize-empty");
+    cmd.arg("--file").arg(utils::get_test_data_file_path(
+        "plans/artificial/empty-values.json",
+    ));
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn limits() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/limits.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--changed-values");
+    cmd.arg("--max-string-length").arg("8");
+    cmd.arg("--max-lines").arg("1");
+    cmd.arg("--max-depth").arg("1");
+    cmd.arg("--max-elements").arg("1");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn sensitive_update() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/sensitive_update.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--file").arg(utils::get_test_data_file_path(
+        "plans/artificial/sensitive-update.json",
+    ));
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn provider_schemas() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/provider_schemas.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--style").arg("terraform");
+    cmd.arg("--provider-schemas")
+        .arg(utils::get_test_data_file_path("schemas/providers.json"));
+    cmd.arg("--file").arg(utils::get_test_data_file_path(
+        "plans/artificial/schema.json",
+    ));
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_provider_schemas() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--provider-schemas").arg("invalid");
+    cmd.arg("--file").arg(utils::get_test_data_file_path(
+        "plans/artificial/schema.json",
+    ));
+
+    cmd.assert().failure();
+    cmd.assert().stdout("");
+    cmd.assert().stderr(
+        "Failed to load provider schemas. Failed to read file(invalid). No such file or directory (os error 2)\n",
+    );
+    cmd.assert().code(65);
+
+    Ok(())
+}
+
+#[test]
+fn filter() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/filter.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--exclude-action").arg("no-op,read");
+    cmd.arg("--exclude-type").arg("random_*");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn group_by_module() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/group_by_module.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--group-by-module");
+    cmd.arg("--file").arg(utils::get_test_data_file_path(
+        "plans/artificial/modules.json",
+    ));
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn group_instances() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/group_instances.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--group-instances");
+    cmd.arg("--file").arg(utils::get_test_data_file_path(
+        "plans/artificial/instances.json",
+    ));
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_filter() -> Result<(), Box<dyn std::error::Error>> {
+    for (arg, value, error) in [
+        (
+            "--exclude-action",
+            "invalid",
+            "Invalid action(invalid). Invalid action: invalid\n",
+        ),
+        (
+            "--exclude-type",
+            "[",
+            "Invalid pattern([). Pattern syntax error near position 0: invalid range pattern\n",
+        ),
+        (
+            "--exclude-mode",
+            "invalid",
+            "Invalid mode(invalid). Invalid resource mode: invalid\n",
+        ),
+    ] {
+        let mut cmd = Command::cargo_bin("tf_plan_format")?;
+        cmd.arg("github");
+        cmd.arg(arg).arg(value);
+
+        for file in utils::get_plan_files() {
+            cmd.arg("--file").arg(file);
+        }
+
+        cmd.assert().failure();
+        cmd.assert().stdout("");
+        cmd.assert().stderr(error);
+        cmd.assert().code(64);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn invalid_files() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("tf_plan_format")?;
@@ -58,3 +472,76 @@ fn invalid_files() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn max_size() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/max_size.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--max-size").arg("500");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn max_size_summary() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--summary");
+    cmd.arg("--max-size").arg("300");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().failure();
+    cmd.assert().stdout("");
+    cmd.assert().stderr(
+        predicates::str::starts_with("Failed to render template. Summary of ").and(
+            predicates::str::ends_with("does not fit into parts of 300 characters\n"),
+        ),
+    );
+    cmd.assert().code(65);
+
+    Ok(())
+}
+
+#[test]
+fn output_dir() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = tempfile::tempdir()?;
+    let output_dir = temp_dir.path().join("parts");
+    let first = output_dir.join("part-1.md").to_string_lossy().to_string();
+    let second = output_dir.join("part-2.md").to_string_lossy().to_string();
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("github");
+    cmd.arg("--max-size").arg("500");
+    cmd.arg("--output-dir").arg(&output_dir);
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(format!("{first}\n{second}\n"));
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/github_markdown/max_size.md");
+    let result = std::fs::read_to_string(first)? + &std::fs::read_to_string(second)?;
+    pretty_assertions::assert_eq!(expected_result + "\n", result);
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use crate::utils;
+use assert_cmd::prelude::*;
+use std::process::Command;
+
+#[test]
+fn default() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result =
+        utils::get_test_data_file_contents("tera/renders/gitlab_markdown/default.md");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("gitlab");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn max_size() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("gitlab");
+    cmd.arg("--max-size").arg("600");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(predicates::str::starts_with(
+        "<!-- part 1/3 -->\n- :white_check_mark: tests/data/plans/create/terraform.tfplan.json: 1 change\n",
+    ));
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_style() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("gitlab");
+    cmd.arg("--style").arg("invalid");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().failure();
+    cmd.assert().stdout("");
+    cmd.assert()
+        .stderr("Invalid style(invalid). Invalid values style: invalid\n");
+    cmd.assert().code(64);
+
+    Ok(())
+}
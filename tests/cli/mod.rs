@@ -1,3 +1,12 @@
+mod adaptive_card;
+mod csv;
 mod custom;
 mod github;
+mod gitlab;
+mod html;
+mod json;
+mod junit;
 mod none;
+mod sarif;
+mod slack;
+mod terminal;
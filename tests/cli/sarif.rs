@@ -0,0 +1,57 @@
+use crate::utils;
+use assert_cmd::prelude::*;
+use std::process::Command;
+
+#[test]
+fn default() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result = utils::get_test_data_file_contents("sarif/default.json");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("sarif");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_files() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("sarif");
+
+    cmd.arg("--file").arg("invalid");
+
+    cmd.assert().failure();
+    cmd.assert().stdout("");
+    cmd.assert()
+        .stderr("Failed to parse plan. Failed to read file(invalid). No files found\n");
+    cmd.assert().code(65);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_action() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("sarif");
+
+    cmd.arg("--report-action").arg("invalid");
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().failure();
+    cmd.assert().stdout("");
+    cmd.assert()
+        .stderr("Invalid action(invalid). Invalid action: invalid\n");
+    cmd.assert().code(64);
+
+    Ok(())
+}
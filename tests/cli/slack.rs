@@ -0,0 +1,58 @@
+use crate::utils;
+use assert_cmd::prelude::*;
+use std::process::Command;
+
+#[test]
+fn default() -> Result<(), Box<dyn std::error::Error>> {
+    let expected_result = utils::get_test_data_file_contents("slack/default.json");
+
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("slack");
+    cmd.arg("--max-changes").arg("1");
+
+    for file in utils::get_plan_files() {
+        cmd.arg("--file").arg(file);
+    }
+
+    cmd.assert().success();
+    cmd.assert().stdout(expected_result + "\n");
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn title() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("slack");
+    cmd.arg("--title").arg("Plan for \"prod\"");
+    cmd.arg("--file").arg(utils::get_test_data_file_path(
+        "plans/no-resources/terraform.tfplan.json",
+    ));
+
+    cmd.assert().success();
+    cmd.assert().stdout(predicates::str::starts_with(
+        r#"{"blocks":[{"type":"header","text":{"type":"plain_text","text":"Plan for \"prod\""}}"#,
+    ));
+    cmd.assert().stderr("");
+    cmd.assert().code(0);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_files() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("tf_plan_format")?;
+    cmd.arg("slack");
+
+    cmd.arg("--file").arg("invalid");
+
+    cmd.assert().failure();
+    cmd.assert().stdout("");
+    cmd.assert()
+        .stderr("Failed to parse plan. Failed to read file(invalid). No files found\n");
+    cmd.assert().code(65);
+
+    Ok(())
+}